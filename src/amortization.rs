@@ -0,0 +1,619 @@
+use crate::currency;
+use crate::currency::Currency;
+use crate::error::Error;
+use crate::fractional_money::FractionalMoney;
+use crate::interest::Compounding;
+use crate::money::Money;
+use crate::rounding::RoundingMode;
+use chrono::{Days, Months, NaiveDate};
+use rust_decimal::{Decimal, MathematicalOps};
+use std::collections::BTreeMap;
+
+/// The rounding method used for payments and interest. Lenders conventionally round half a cent
+/// up.
+const ROUNDING: RoundingMode = RoundingMode::MidpointAwayFromZero;
+
+/// How often loan payments are made.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum PaymentFrequency {
+    Weekly,
+    BiWeekly,
+    #[default]
+    Monthly,
+    Quarterly,
+    SemiAnnual,
+    Annual,
+    /// Every two weeks, paying half the monthly payment. This amounts to an extra monthly payment
+    /// each year, which shortens the loan.
+    AcceleratedBiWeekly,
+    /// Every week, paying a quarter of the monthly payment. This amounts to an extra monthly
+    /// payment each year, which shortens the loan.
+    AcceleratedWeekly,
+}
+
+impl PaymentFrequency {
+    /// The number of payments per year.
+    pub fn periods_per_year(&self) -> u32 {
+        match self {
+            Self::Weekly | Self::AcceleratedWeekly => 52,
+            Self::BiWeekly | Self::AcceleratedBiWeekly => 26,
+            Self::Monthly => 12,
+            Self::Quarterly => 4,
+            Self::SemiAnnual => 2,
+            Self::Annual => 1,
+        }
+    }
+
+    /// The date of payment number `number`, counting from 1, for a loan starting on `start`.
+    /// Month-based dates are clamped to the end of shorter months, e.g., a loan starting on
+    /// January 31 has its first monthly payment on February 28 (or 29).
+    fn payment_date(&self, start: NaiveDate, number: u32) -> Option<NaiveDate> {
+        match self {
+            Self::Weekly | Self::AcceleratedWeekly => {
+                start.checked_add_days(Days::new(7 * number as u64))
+            }
+            Self::BiWeekly | Self::AcceleratedBiWeekly => {
+                start.checked_add_days(Days::new(14 * number as u64))
+            }
+            Self::Monthly => start.checked_add_months(Months::new(number)),
+            Self::Quarterly => start.checked_add_months(Months::new(3 * number)),
+            Self::SemiAnnual => start.checked_add_months(Months::new(6 * number)),
+            Self::Annual => start.checked_add_months(Months::new(12 * number)),
+        }
+    }
+
+    /// The number of payments per monthly payment, for accelerated frequencies.
+    fn acceleration(&self) -> Option<u32> {
+        match self {
+            Self::AcceleratedBiWeekly => Some(2),
+            Self::AcceleratedWeekly => Some(4),
+            _ => None,
+        }
+    }
+}
+
+/// How a loan's payments are structured.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum LoanKind {
+    /// Equal payments, each covering the interest due and an increasing share of principal.
+    #[default]
+    FixedPayment,
+    /// An equal share of the principal each period, plus the interest due.
+    FixedPrincipal,
+    /// Only the interest due each period, with the whole principal repaid in the final payment.
+    InterestOnly,
+}
+
+/// A loan to be repaid in regular payments, from which an amortization schedule is produced.
+///
+/// Interest for each period is the balance times the periodic rate (by default, the annual rate
+/// divided by the number of payments per year; see `with_rate_compounding()`), and it is rounded
+/// to the minor unit of the currency, as are payments. The final payment absorbs any rounding
+/// differences so that the balance ends at exactly zero.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Loan {
+    principal: Money,
+    annual_rate: Decimal,
+    periods: u32,
+    frequency: PaymentFrequency,
+    start_date: NaiveDate,
+    kind: LoanKind,
+    rate_compounding: Option<Compounding>,
+    prepayments: BTreeMap<u32, Money>,
+}
+
+impl Loan {
+    /// Creates a fixed-payment loan of `principal` at the nominal `annual_rate` (e.g.,
+    /// `dec!(0.06)` for 6%), repaid in `periods` payments of the given frequency, the first of
+    /// which is one period after `start_date`. Returns an error if the principal is not
+    /// positive, the rate is negative, or there are no periods.
+    pub fn new(
+        principal: Money,
+        annual_rate: Decimal,
+        periods: u32,
+        frequency: PaymentFrequency,
+        start_date: NaiveDate,
+    ) -> Result<Self, Error> {
+        if !principal.is_positive() {
+            return Err(Error::InvalidArgument(format!(
+                "expected a positive principal, but got {principal}"
+            )));
+        }
+        if annual_rate.is_sign_negative() {
+            return Err(Error::InvalidArgument(format!(
+                "expected a non-negative annual rate, but got {annual_rate}"
+            )));
+        }
+        if periods == 0 {
+            return Err(Error::InvalidArgument(
+                "expected at least one period".to_string(),
+            ));
+        }
+        Ok(Self {
+            principal,
+            annual_rate,
+            periods,
+            frequency,
+            start_date,
+            kind: LoanKind::FixedPayment,
+            rate_compounding: None,
+            prepayments: BTreeMap::new(),
+        })
+    }
+
+    /// Creates a Canadian fixed-rate mortgage, whose quoted rate is, by law, a nominal annual rate
+    /// compounded semi-annually (not in advance). The number of payments is the number in
+    /// `amortization_years` at the given frequency, although accelerated payments repay the loan
+    /// sooner. Returns an error if the principal is not in CAD, or for the same reasons as
+    /// `new()`.
+    pub fn canadian_mortgage(
+        principal: Money,
+        quoted_rate: Decimal,
+        amortization_years: u32,
+        frequency: PaymentFrequency,
+        start_date: NaiveDate,
+    ) -> Result<Self, Error> {
+        if principal.currency() != Currency::CAD {
+            return Err(Error::InvalidArgument(format!(
+                "expected a principal in CAD, but got {principal}"
+            )));
+        }
+        let periods = amortization_years * frequency.periods_per_year();
+        Ok(
+            Self::new(principal, quoted_rate, periods, frequency, start_date)?
+                .with_rate_compounding(Compounding::SemiAnnual),
+        )
+    }
+
+    /// Sets how often the annual rate is compounded, if not with every payment. The periodic rate
+    /// is then the equivalent rate per payment period, e.g., for a rate compounded semi-annually
+    /// with monthly payments, `(1 + rate / 2)^(2 / 12) - 1`.
+    pub fn with_rate_compounding(mut self, compounding: Compounding) -> Self {
+        self.rate_compounding = Some(compounding);
+        self
+    }
+
+    /// Sets how the loan's payments are structured.
+    pub fn with_kind(mut self, kind: LoanKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Adds an extra payment of principal along with payment number `period`, counting from 1.
+    /// Fixed payments stay the same afterwards, so prepayments shorten the loan. Returns an error
+    /// if the amount is negative or in a different currency from the principal.
+    pub fn with_prepayment(mut self, period: u32, amount: Money) -> Result<Self, Error> {
+        currency::combine_currency(self.principal.currency(), amount.currency())?;
+        if amount.is_negative() {
+            return Err(Error::InvalidArgument(format!(
+                "expected a non-negative prepayment, but got {amount}"
+            )));
+        }
+        let total = match self.prepayments.get(&period) {
+            Some(existing) => existing.try_add(&amount)?,
+            None => amount,
+        };
+        self.prepayments.insert(period, total);
+        Ok(self)
+    }
+
+    pub fn principal(&self) -> Money {
+        self.principal
+    }
+
+    pub fn kind(&self) -> LoanKind {
+        self.kind
+    }
+
+    /// The interest rate charged per payment period. Returns an error if the calculation
+    /// overflows.
+    pub fn periodic_rate(&self) -> Result<Decimal, Error> {
+        let periods_per_year = self.frequency.periods_per_year();
+        match self.rate_compounding {
+            None => Ok(self.annual_rate / Decimal::from(periods_per_year)),
+            Some(compounding) => compounding
+                .equivalent_periodic_rate(self.annual_rate, periods_per_year)
+                .ok_or(Error::Overflow),
+        }
+    }
+
+    /// The regular payment of a fixed-payment loan, i.e., the amount which repays the principal
+    /// with interest in equal payments. The final payment may differ slightly due to rounding.
+    /// For accelerated frequencies, it is the corresponding fraction of the monthly payment over
+    /// the same term. Returns an error if the calculation overflows, or if an accelerated loan's
+    /// term is not a whole number of months.
+    pub fn fixed_payment(&self) -> Result<Money, Error> {
+        if let Some(acceleration) = self.frequency.acceleration() {
+            let months = self.periods * 12;
+            let periods_per_year = self.frequency.periods_per_year();
+            if !months.is_multiple_of(periods_per_year) {
+                return Err(Error::InvalidArgument(format!(
+                    "expected an accelerated loan's term to be a whole number of months, but it \
+                     is {} payments of {:?}",
+                    self.periods, self.frequency
+                )));
+            }
+            let monthly = Self {
+                periods: months / periods_per_year,
+                frequency: PaymentFrequency::Monthly,
+                ..self.clone()
+            };
+            let payment = monthly.fixed_payment()? / Decimal::from(acceleration);
+            return Ok(payment.round_with(ROUNDING));
+        }
+
+        let principal = self.principal.amount();
+        let periods = Decimal::from(self.periods);
+        let rate = self.periodic_rate()?;
+        let payment = if rate.is_zero() {
+            principal.checked_div(periods)
+        } else {
+            // P * r / (1 - (1 + r)^-n) = P * r * f / (f - 1), where f = (1 + r)^n.
+            (Decimal::ONE + rate)
+                .checked_powi(self.periods.into())
+                .and_then(|f| {
+                    principal
+                        .checked_mul(rate)?
+                        .checked_mul(f)?
+                        .checked_div(f - Decimal::ONE)
+                })
+        };
+        let payment = payment.ok_or(Error::Overflow)?;
+        Ok(FractionalMoney::new(payment, self.principal.currency())?.round_with(ROUNDING))
+    }
+
+    /// Produces the amortization schedule. It has fewer than the given number of periods if
+    /// prepayments repay the loan early. Returns an error if the calculation overflows.
+    pub fn schedule(&self) -> Result<Schedule, Error> {
+        let rate = self.periodic_rate()?;
+        let zero = Money::new(Decimal::ZERO, self.principal.currency())?;
+        let repayment = match self.kind {
+            LoanKind::FixedPayment => Repayment::Payment(self.fixed_payment()?),
+            LoanKind::FixedPrincipal => Repayment::Principal(
+                (self.principal / Decimal::from(self.periods)).round_with(ROUNDING),
+            ),
+            LoanKind::InterestOnly => Repayment::Principal(zero),
+        };
+
+        let mut balance = self.principal;
+        let mut periods = vec![];
+        for number in 1..=self.periods {
+            let interest = (balance * rate).round_with(ROUNDING);
+            let mut principal = match repayment {
+                Repayment::Payment(payment) => payment.try_subtract(&interest)?,
+                Repayment::Principal(principal) => principal,
+            };
+            if number == self.periods || principal > balance {
+                principal = balance;
+            }
+            balance = balance.try_subtract(&principal)?;
+
+            let mut prepayment = self.prepayments.get(&number).copied().unwrap_or(zero);
+            if prepayment > balance {
+                prepayment = balance;
+            }
+            balance = balance.try_subtract(&prepayment)?;
+
+            periods.push(Period {
+                number,
+                date: self
+                    .frequency
+                    .payment_date(self.start_date, number)
+                    .ok_or(Error::Overflow)?,
+                payment: interest.try_add(&principal)?,
+                interest,
+                principal,
+                prepayment,
+                balance,
+            });
+            if balance.is_zero() {
+                break;
+            }
+        }
+        Ok(Schedule { periods })
+    }
+}
+
+/// The regular amount repaid each period, which depends on the `LoanKind`.
+#[derive(Copy, Clone, Debug)]
+enum Repayment {
+    /// A fixed payment, of which the principal is what remains after the interest.
+    Payment(Money),
+    /// A fixed share of the principal, paid in addition to the interest.
+    Principal(Money),
+}
+
+/// A single period of an amortization schedule.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Period {
+    /// The payment number, counting from 1.
+    pub number: u32,
+    pub date: NaiveDate,
+    /// The regular payment, i.e., `interest + principal`, excluding any prepayment.
+    pub payment: Money,
+    pub interest: Money,
+    pub principal: Money,
+    pub prepayment: Money,
+    /// The balance remaining after the payment and prepayment.
+    pub balance: Money,
+}
+
+/// An amortization schedule, i.e., the breakdown of every payment of a loan.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Schedule {
+    periods: Vec<Period>,
+}
+
+impl Schedule {
+    pub fn periods(&self) -> &[Period] {
+        &self.periods
+    }
+
+    /// The total interest paid over the life of the loan.
+    pub fn total_interest(&self) -> Money {
+        self.periods.iter().map(|p| p.interest).sum()
+    }
+
+    /// The total amount paid over the life of the loan, including prepayments.
+    pub fn total_paid(&self) -> Money {
+        self.periods.iter().map(|p| p.payment + p.prepayment).sum()
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::{cad, usd};
+    use anyhow::Result;
+    use expecting::*;
+    use rust_decimal_macros::dec;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn mortgage() -> Loan {
+        Loan::new(
+            usd!(200000),
+            dec!(0.06),
+            360,
+            PaymentFrequency::Monthly,
+            date(2024, 1, 15),
+        )
+        .unwrap()
+    }
+
+    fn expect_consistent(loan: &Loan, schedule: &Schedule) -> Result<()> {
+        let mut balance = loan.principal();
+        for period in schedule.periods() {
+            expect_eq!(period.payment, period.interest + period.principal);
+            balance = balance - period.principal - period.prepayment;
+            expect_eq!(period.balance, balance);
+        }
+        expect!(balance.is_zero());
+        Ok(())
+    }
+
+    #[test]
+    fn fixed_payment() -> Result<()> {
+        let loan = mortgage();
+        expect_eq!(loan.periodic_rate()?, dec!(0.005));
+        expect_eq!(expect_ok!(loan.fixed_payment()), usd!(1199.10));
+        Ok(())
+    }
+
+    #[test]
+    fn schedule__fixed_payment() -> Result<()> {
+        let loan = mortgage();
+        let schedule = expect_ok!(loan.schedule());
+        let periods = schedule.periods();
+        expect_eq!(periods.len(), 360);
+        expect_consistent(&loan, &schedule)?;
+
+        expect_eq!(
+            periods[0],
+            Period {
+                number: 1,
+                date: date(2024, 2, 15),
+                payment: usd!(1199.10),
+                interest: usd!(1000.00),
+                principal: usd!(199.10),
+                prepayment: usd!(0),
+                balance: usd!(199800.90),
+            }
+        );
+        expect_eq!(periods[1].interest, usd!(999.00));
+        expect!(periods[..359].iter().all(|p| p.payment == usd!(1199.10)));
+
+        let last = periods[359];
+        expect_eq!(last.date, date(2054, 1, 15));
+        expect_eq!(last.balance, usd!(0));
+        expect_ne!(last.payment, usd!(1199.10));
+        expect_eq!(
+            schedule.total_paid(),
+            usd!(200000) + schedule.total_interest()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn schedule__zero_rate() -> Result<()> {
+        let loan = Loan::new(
+            usd!(100),
+            dec!(0),
+            3,
+            PaymentFrequency::Monthly,
+            date(2024, 1, 31),
+        )?;
+        let schedule = loan.schedule()?;
+        let payments: Vec<Money> = schedule.periods().iter().map(|p| p.payment).collect();
+        expect_eq!(payments, vec![usd!(33.33), usd!(33.33), usd!(33.34)]);
+        expect_eq!(schedule.periods()[0].date, date(2024, 2, 29));
+        expect_eq!(schedule.total_interest(), usd!(0));
+        Ok(())
+    }
+
+    #[test]
+    fn schedule__fixed_principal() -> Result<()> {
+        let loan = Loan::new(
+            cad!(1000),
+            dec!(0.12),
+            3,
+            PaymentFrequency::Monthly,
+            date(2024, 1, 1),
+        )?
+        .with_kind(LoanKind::FixedPrincipal);
+        let schedule = loan.schedule()?;
+        expect_consistent(&loan, &schedule)?;
+        let periods = schedule.periods();
+        expect_eq!(periods[0].principal, cad!(333.33));
+        expect_eq!(periods[0].interest, cad!(10.00));
+        expect_eq!(periods[1].interest, cad!(6.67));
+        expect_eq!(periods[2].principal, cad!(333.34));
+        expect_eq!(periods[2].interest, cad!(3.33));
+        Ok(())
+    }
+
+    #[test]
+    fn schedule__interest_only() -> Result<()> {
+        let loan = Loan::new(
+            usd!(1000),
+            dec!(0.052),
+            4,
+            PaymentFrequency::Quarterly,
+            date(2024, 1, 1),
+        )?
+        .with_kind(LoanKind::InterestOnly);
+        let schedule = loan.schedule()?;
+        expect_consistent(&loan, &schedule)?;
+        let payments: Vec<Money> = schedule.periods().iter().map(|p| p.payment).collect();
+        expect_eq!(payments, vec![usd!(13), usd!(13), usd!(13), usd!(1013)]);
+        expect_eq!(schedule.periods()[3].date, date(2025, 1, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn schedule__prepayments_shorten_loan() -> Result<()> {
+        let loan = mortgage()
+            .with_prepayment(1, usd!(10000))?
+            .with_prepayment(12, usd!(5000))?
+            .with_prepayment(12, usd!(5000))?;
+        let schedule = loan.schedule()?;
+        expect_consistent(&loan, &schedule)?;
+        let periods = schedule.periods();
+        expect_eq!(periods[0].prepayment, usd!(10000));
+        expect_eq!(periods[0].balance, usd!(189800.90));
+        expect_eq!(periods[1].payment, usd!(1199.10));
+        expect_eq!(periods[11].prepayment, usd!(10000));
+        expect!(periods.len() < 300);
+        expect!(schedule.total_interest() < mortgage().schedule()?.total_interest());
+        Ok(())
+    }
+
+    #[test]
+    fn schedule__prepayment_larger_than_balance() -> Result<()> {
+        let loan = Loan::new(
+            usd!(1000),
+            dec!(0.12),
+            12,
+            PaymentFrequency::Weekly,
+            date(2024, 1, 1),
+        )?
+        .with_prepayment(2, usd!(5000))?;
+        let schedule = loan.schedule()?;
+        expect_consistent(&loan, &schedule)?;
+        expect_eq!(schedule.periods().len(), 2);
+        expect_eq!(schedule.periods()[1].date, date(2024, 1, 15));
+        expect!(schedule.periods()[1].prepayment < usd!(1000));
+        Ok(())
+    }
+
+    fn canadian_mortgage(frequency: PaymentFrequency) -> Loan {
+        Loan::canadian_mortgage(cad!(100000), dec!(0.06), 25, frequency, date(2024, 1, 1)).unwrap()
+    }
+
+    #[test]
+    fn canadian_mortgage__payments() -> Result<()> {
+        let payment = |frequency| canadian_mortgage(frequency).fixed_payment().unwrap();
+        expect_eq!(payment(PaymentFrequency::Monthly), cad!(639.81));
+        expect_eq!(payment(PaymentFrequency::BiWeekly), cad!(294.90));
+        expect_eq!(payment(PaymentFrequency::Weekly), cad!(147.37));
+        expect_eq!(payment(PaymentFrequency::AcceleratedBiWeekly), cad!(319.91));
+        expect_eq!(payment(PaymentFrequency::AcceleratedWeekly), cad!(159.95));
+        Ok(())
+    }
+
+    #[test]
+    fn canadian_mortgage__schedule() -> Result<()> {
+        let loan = canadian_mortgage(PaymentFrequency::Monthly);
+        expect_eq!(loan.periodic_rate()?.round_dp(10), dec!(0.0049386220));
+        let schedule = loan.schedule()?;
+        expect_consistent(&loan, &schedule)?;
+        let periods = schedule.periods();
+        expect_eq!(periods.len(), 300);
+        expect_eq!(periods[0].interest, cad!(493.86));
+        expect_eq!(periods[0].principal, cad!(145.95));
+        expect_eq!(periods[299].date, date(2049, 1, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn canadian_mortgage__accelerated_schedule_is_shorter() -> Result<()> {
+        let bi_weekly = canadian_mortgage(PaymentFrequency::BiWeekly).schedule()?;
+        let loan = canadian_mortgage(PaymentFrequency::AcceleratedBiWeekly);
+        let accelerated = loan.schedule()?;
+        expect_consistent(&loan, &accelerated)?;
+        expect_eq!(bi_weekly.periods().len(), 650);
+        expect!(accelerated.periods().len() < 600);
+        expect!(accelerated.total_interest() < bi_weekly.total_interest());
+        expect_eq!(accelerated.periods()[0].date, date(2024, 1, 15));
+        Ok(())
+    }
+
+    #[test]
+    fn canadian_mortgage__not_cad__returns_err() -> Result<()> {
+        let monthly = PaymentFrequency::Monthly;
+        expect_err!(Loan::canadian_mortgage(
+            usd!(100000),
+            dec!(0.06),
+            25,
+            monthly,
+            date(2024, 1, 1)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn fixed_payment__accelerated_partial_month__returns_err() -> Result<()> {
+        let loan = Loan::new(
+            cad!(1000),
+            dec!(0.06),
+            27,
+            PaymentFrequency::AcceleratedBiWeekly,
+            date(2024, 1, 1),
+        )?;
+        expect_err!(loan.fixed_payment());
+        Ok(())
+    }
+
+    #[test]
+    fn new__invalid_arguments__returns_err() -> Result<()> {
+        let start = date(2024, 1, 1);
+        let monthly = PaymentFrequency::Monthly;
+        expect_err!(Loan::new(usd!(0), dec!(0.05), 12, monthly, start));
+        expect_err!(Loan::new(usd!(100), dec!(-0.05), 12, monthly, start));
+        expect_err!(Loan::new(usd!(100), dec!(0.05), 0, monthly, start));
+        Ok(())
+    }
+
+    #[test]
+    fn with_prepayment__mismatched_currency__returns_err() -> Result<()> {
+        expect_eq!(
+            expect_err!(mortgage().with_prepayment(1, cad!(100))),
+            Error::MismatchedCurrency
+        );
+        expect_err!(mortgage().with_prepayment(1, usd!(-100)));
+        Ok(())
+    }
+}
@@ -0,0 +1,372 @@
+use crate::currency;
+use crate::currency::Currency;
+use crate::error::Error;
+use crate::fractional_money::FractionalMoney;
+use crate::money::Money;
+use crate::rounding::{RoundingAccumulator, RoundingMode};
+use rust_decimal::Decimal;
+
+/// What a bracket charges.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Tier {
+    /// A fraction of the amount, e.g., `Tier::Rate(dec!(0.22))` for 22%.
+    Rate(Decimal),
+    /// A fixed charge.
+    Flat(Money),
+}
+
+/// How the brackets of a `BracketSchedule` apply to an amount.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum BracketMode {
+    /// Each bracket applies to the slice of the amount within it, as with income tax.
+    #[default]
+    Graduated,
+    /// The highest bracket reached applies to the whole amount, as with volume pricing.
+    AllUnits,
+}
+
+/// A bracket, which applies to amounts from its threshold up to the next bracket's threshold.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Bracket {
+    pub from: Money,
+    pub tier: Tier,
+}
+
+impl Bracket {
+    pub fn new(from: Money, tier: Tier) -> Self {
+        Self { from, tier }
+    }
+}
+
+/// The part of a `BracketSchedule`'s result due to a single bracket.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BracketSlice {
+    pub bracket: Bracket,
+    /// The threshold of the next bracket, if any.
+    pub to: Option<Money>,
+    /// The part of the amount the bracket applies to.
+    pub amount: Money,
+    pub charge: Money,
+}
+
+/// The result of applying a `BracketSchedule` to an amount.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BracketResult {
+    pub total: Money,
+    /// The brackets which apply to the amount, in order. The charges sum to `total`.
+    pub slices: Vec<BracketSlice>,
+    /// The rate of the highest bracket reached, i.e., the rate which applies to the next unit of
+    /// the amount. It is zero for flat tiers and for amounts below the first threshold.
+    pub marginal_rate: Decimal,
+    /// The total divided by the amount, or zero if the amount is zero.
+    pub average_rate: Decimal,
+}
+
+/// A schedule of thresholds and the rates or flat amounts which apply above them, e.g., income
+/// tax brackets, tiered commissions or volume pricing.
+///
+/// The total is calculated exactly and rounded once, and each bracket's charge is rounded so that
+/// the charges add up to the total.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BracketSchedule {
+    brackets: Vec<Bracket>,
+    currency: Currency,
+    mode: BracketMode,
+    rounding: RoundingMode,
+}
+
+impl BracketSchedule {
+    /// Returns an error if there are no brackets, their thresholds are not in increasing order,
+    /// or their currencies do not match.
+    pub fn new(brackets: Vec<Bracket>, mode: BracketMode) -> Result<Self, Error> {
+        if brackets.is_empty() {
+            return Err(Error::InvalidArgument(
+                "expected at least one bracket".to_string(),
+            ));
+        }
+        let mut currency = Currency::ZeroNone;
+        for bracket in &brackets {
+            currency = currency::combine_currency(currency, bracket.from.currency())?;
+            if let Tier::Flat(amount) = bracket.tier {
+                currency = currency::combine_currency(currency, amount.currency())?;
+            }
+        }
+        for pair in brackets.windows(2) {
+            if pair[1].from <= pair[0].from {
+                return Err(Error::InvalidArgument(format!(
+                    "expected increasing thresholds, but {} follows {}",
+                    pair[1].from, pair[0].from
+                )));
+            }
+        }
+        Ok(Self {
+            brackets,
+            currency,
+            mode,
+            rounding: RoundingMode::default(),
+        })
+    }
+
+    /// Sets the rounding method used for the total and the brackets' charges.
+    pub fn with_rounding(mut self, rounding: RoundingMode) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    pub fn brackets(&self) -> &[Bracket] {
+        &self.brackets
+    }
+
+    /// The currency of the thresholds and flat charges, which is `ZeroNone` if they are all zero.
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    pub fn mode(&self) -> BracketMode {
+        self.mode
+    }
+
+    /// Applies the schedule to an amount. Returns an error if the amount is negative or its
+    /// currency does not match the brackets'.
+    pub fn calculate(&self, amount: Money) -> Result<BracketResult, Error> {
+        let currency = currency::combine_currency(self.currency, amount.currency())?;
+        if amount.is_negative() {
+            return Err(Error::InvalidArgument(format!(
+                "expected a non-negative amount, but got {amount}"
+            )));
+        }
+
+        // The brackets reached, with the part of the amount each applies to.
+        let mut reached = vec![];
+        for (i, bracket) in self.brackets.iter().enumerate() {
+            if amount < bracket.from {
+                break;
+            }
+            let to = self.brackets.get(i + 1).map(|next| next.from);
+            let upper = match to {
+                Some(to) if to < amount => to,
+                _ => amount,
+            };
+            reached.push((*bracket, to, upper.try_subtract(&bracket.from)?));
+        }
+        if self.mode == BracketMode::AllUnits {
+            reached = reached
+                .pop()
+                .map(|(bracket, to, _)| vec![(bracket, to, amount)])
+                .unwrap_or_default();
+        }
+
+        let mut accumulator = RoundingAccumulator::new(self.rounding);
+        let mut slices = vec![];
+        for (bracket, to, slice) in reached {
+            let exact = match bracket.tier {
+                Tier::Rate(rate) => {
+                    let charge = slice.amount().checked_mul(rate).ok_or(Error::Overflow)?;
+                    FractionalMoney::new(charge, currency)?
+                }
+                Tier::Flat(charge) => charge.into(),
+            };
+            slices.push(BracketSlice {
+                bracket,
+                to,
+                amount: slice,
+                charge: accumulator.push(&exact)?.amount,
+            });
+        }
+
+        let total = accumulator.rounded_total();
+        let total = if total.currency() == Currency::ZeroNone {
+            Money::new(Decimal::ZERO, currency)?
+        } else {
+            total
+        };
+        let marginal_rate = match slices.last().map(|s| s.bracket.tier) {
+            Some(Tier::Rate(rate)) => rate,
+            _ => Decimal::ZERO,
+        };
+        let average_rate = if amount.is_zero() {
+            Decimal::ZERO
+        } else {
+            total.try_ratio(&amount)?
+        };
+        Ok(BracketResult {
+            total,
+            slices,
+            marginal_rate,
+            average_rate,
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::{cad, usd};
+    use anyhow::Result;
+    use expecting::*;
+    use rust_decimal_macros::dec;
+
+    fn income_tax() -> BracketSchedule {
+        BracketSchedule::new(
+            vec![
+                Bracket::new(usd!(0), Tier::Rate(dec!(0.10))),
+                Bracket::new(usd!(11600), Tier::Rate(dec!(0.12))),
+                Bracket::new(usd!(47150), Tier::Rate(dec!(0.22))),
+            ],
+            BracketMode::Graduated,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn calculate__graduated() -> Result<()> {
+        let result = expect_ok!(income_tax().calculate(usd!(50000)));
+        expect_eq!(result.total, usd!(6053));
+        let charges: Vec<Money> = result.slices.iter().map(|s| s.charge).collect();
+        expect_eq!(charges, vec![usd!(1160), usd!(4266), usd!(627)]);
+        let amounts: Vec<Money> = result.slices.iter().map(|s| s.amount).collect();
+        expect_eq!(amounts, vec![usd!(11600), usd!(35550), usd!(2850)]);
+        expect_eq!(result.slices[0].to, Some(usd!(11600)));
+        expect_eq!(result.slices[2].to, None);
+        expect_eq!(result.marginal_rate, dec!(0.22));
+        expect_eq!(result.average_rate, dec!(0.12106));
+        Ok(())
+    }
+
+    #[test]
+    fn calculate__graduated__within_first_bracket() -> Result<()> {
+        let result = income_tax().calculate(usd!(1000))?;
+        expect_eq!(result.total, usd!(100));
+        expect_eq!(result.slices.len(), 1);
+        expect_eq!(result.marginal_rate, dec!(0.10));
+
+        let result = income_tax().calculate(usd!(0))?;
+        expect_eq!(result.total, usd!(0));
+        expect_eq!(result.total.currency(), Currency::USD);
+        expect_eq!(result.average_rate, dec!(0));
+        Ok(())
+    }
+
+    #[test]
+    fn calculate__all_units() -> Result<()> {
+        let pricing = BracketSchedule::new(
+            vec![
+                Bracket::new(usd!(0), Tier::Rate(dec!(0.05))),
+                Bracket::new(usd!(1000), Tier::Rate(dec!(0.07))),
+            ],
+            BracketMode::AllUnits,
+        )?;
+        let result = pricing.calculate(usd!(1500))?;
+        expect_eq!(result.total, usd!(105));
+        expect_eq!(result.slices.len(), 1);
+        expect_eq!(result.slices[0].amount, usd!(1500));
+        expect_eq!(result.average_rate, dec!(0.07));
+
+        expect_eq!(pricing.calculate(usd!(999.99))?.total, usd!(50.00));
+        expect_eq!(pricing.calculate(usd!(1000))?.total, usd!(70));
+        Ok(())
+    }
+
+    #[test]
+    fn calculate__flat_tiers() -> Result<()> {
+        let brackets = vec![
+            Bracket::new(cad!(0), Tier::Flat(cad!(10))),
+            Bracket::new(cad!(100), Tier::Flat(cad!(5))),
+            Bracket::new(cad!(500), Tier::Rate(dec!(0.01))),
+        ];
+        let graduated = BracketSchedule::new(brackets.clone(), BracketMode::Graduated)?;
+        let result = graduated.calculate(cad!(150))?;
+        expect_eq!(result.total, cad!(15));
+        expect_eq!(result.marginal_rate, dec!(0));
+        expect_eq!(graduated.calculate(cad!(600))?.total, cad!(16));
+
+        let all_units = BracketSchedule::new(brackets, BracketMode::AllUnits)?;
+        expect_eq!(all_units.calculate(cad!(150))?.total, cad!(5));
+        expect_eq!(all_units.calculate(cad!(600))?.total, cad!(6));
+        Ok(())
+    }
+
+    #[test]
+    fn calculate__below_first_threshold() -> Result<()> {
+        let commission = BracketSchedule::new(
+            vec![Bracket::new(usd!(1000), Tier::Rate(dec!(0.1)))],
+            BracketMode::Graduated,
+        )?;
+        let result = commission.calculate(usd!(500))?;
+        expect_eq!(result.total, usd!(0));
+        expect_empty!(result.slices);
+        expect_eq!(commission.calculate(usd!(1500))?.total, usd!(50));
+        Ok(())
+    }
+
+    #[test]
+    fn calculate__charges_sum_to_rounded_total() -> Result<()> {
+        let schedule = BracketSchedule::new(
+            vec![
+                Bracket::new(usd!(0), Tier::Rate(dec!(0.015))),
+                Bracket::new(usd!(0.50), Tier::Rate(dec!(0.015))),
+                Bracket::new(usd!(1.00), Tier::Rate(dec!(0.015))),
+            ],
+            BracketMode::Graduated,
+        )?
+        .with_rounding(RoundingMode::MidpointAwayFromZero);
+        // 0.0075 + 0.0075 + 0.0075 = 0.0225
+        let result = schedule.calculate(usd!(1.50))?;
+        expect_eq!(result.total, usd!(0.02));
+        let charges: Vec<Money> = result.slices.iter().map(|s| s.charge).collect();
+        expect_eq!(charges, vec![usd!(0.01), usd!(0.01), usd!(0)]);
+        Ok(())
+    }
+
+    #[test]
+    fn new__invalid__returns_err() -> Result<()> {
+        expect_err!(BracketSchedule::new(vec![], BracketMode::Graduated));
+        expect_err!(BracketSchedule::new(
+            vec![
+                Bracket::new(usd!(100), Tier::Rate(dec!(0.1))),
+                Bracket::new(usd!(100), Tier::Rate(dec!(0.2))),
+            ],
+            BracketMode::Graduated
+        ));
+        expect_eq!(
+            expect_err!(BracketSchedule::new(
+                vec![
+                    Bracket::new(usd!(0), Tier::Rate(dec!(0.1))),
+                    Bracket::new(usd!(100), Tier::Flat(cad!(1))),
+                ],
+                BracketMode::Graduated
+            )),
+            Error::MismatchedCurrency
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn calculate__invalid__returns_err() -> Result<()> {
+        expect_err!(income_tax().calculate(usd!(-1)));
+        expect_eq!(
+            expect_err!(income_tax().calculate(cad!(1))),
+            Error::MismatchedCurrency
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn calculate__zero_first_threshold__mismatched_currency__returns_err() -> Result<()> {
+        let schedule = BracketSchedule::new(
+            vec![
+                Bracket::new(Money::zero(), Tier::Rate(dec!(0.1))),
+                Bracket::new(usd!(100), Tier::Rate(dec!(0.2))),
+            ],
+            BracketMode::Graduated,
+        )?;
+        expect_eq!(schedule.currency(), Currency::USD);
+        expect_eq!(
+            expect_err!(schedule.calculate(cad!(150))),
+            Error::MismatchedCurrency
+        );
+        expect_eq!(expect_ok!(schedule.calculate(usd!(150))).total, usd!(20));
+        Ok(())
+    }
+}
@@ -0,0 +1,308 @@
+use crate::currency;
+use crate::currency::Currency;
+use crate::error::Error;
+use crate::fractional_money::FractionalMoney;
+use crate::money::Money;
+use crate::rounding::RoundingMode;
+use rust_decimal::Decimal;
+
+/// What to do when the result of an operation is outside the range of `Decimal`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Return an `Overflow` error.
+    #[default]
+    Error,
+    /// Clamp the result to the largest (or smallest) representable value.
+    Saturate,
+}
+
+/// A policy for performing `FractionalMoney` calculations: the precision kept in intermediate
+/// results, the rounding method, what to do on overflow, and whether to round to cash
+/// denominations. This lets the policy be declared once and applied consistently by passing the
+/// context to each operation. Use struct update syntax to override the defaults, e.g.,
+/// `MoneyContext { max_scale: 6, ..Default::default() }`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MoneyContext {
+    /// The maximum number of decimal places kept in the result of each operation. Results with
+    /// more decimal places are rounded using `rounding`. Values above 28 (the maximum scale of
+    /// `Decimal`) have no effect.
+    pub max_scale: u32,
+    /// The rounding method used both for intermediate results and by `round()`.
+    pub rounding: RoundingMode,
+    /// What to do when the result of an operation overflows.
+    pub overflow: OverflowPolicy,
+    /// If true, `round()` rounds to the smallest denomination used for cash payments (see
+    /// `Currency::cash_increment()`) rather than to the minor unit of the currency.
+    pub cash_rounding: bool,
+}
+
+impl Default for MoneyContext {
+    fn default() -> Self {
+        Self {
+            max_scale: 28,
+            rounding: RoundingMode::MidpointNearestEven,
+            overflow: OverflowPolicy::Error,
+            cash_rounding: false,
+        }
+    }
+}
+
+impl MoneyContext {
+    /// Adds two monetary values. Returns an error if the currencies do not match.
+    pub fn add(
+        &self,
+        lhs: &FractionalMoney,
+        rhs: &FractionalMoney,
+    ) -> Result<FractionalMoney, Error> {
+        let currency = currency::combine_currency(lhs.currency(), rhs.currency())?;
+        match lhs.try_add(rhs) {
+            Err(Error::Overflow) => self.overflowed(currency, lhs.is_negative()),
+            result => Ok(self.limit_scale(result?)),
+        }
+    }
+
+    /// Subtracts `rhs` from `lhs`. Returns an error if the currencies do not match.
+    pub fn subtract(
+        &self,
+        lhs: &FractionalMoney,
+        rhs: &FractionalMoney,
+    ) -> Result<FractionalMoney, Error> {
+        let currency = currency::combine_currency(lhs.currency(), rhs.currency())?;
+        match lhs.try_subtract(rhs) {
+            Err(Error::Overflow) => self.overflowed(currency, lhs.is_negative()),
+            result => Ok(self.limit_scale(result?)),
+        }
+    }
+
+    /// Multiplies a monetary value by a scalar.
+    pub fn multiply(
+        &self,
+        value: &FractionalMoney,
+        scalar: Decimal,
+    ) -> Result<FractionalMoney, Error> {
+        match value.amount().checked_mul(scalar) {
+            Some(amount) => self.finish(amount, value.currency()),
+            None => self.overflowed(
+                value.currency(),
+                value.is_negative() != scalar.is_sign_negative(),
+            ),
+        }
+    }
+
+    /// Divides a monetary value by a scalar. Returns an error if `scalar` is zero.
+    pub fn divide(
+        &self,
+        value: &FractionalMoney,
+        scalar: Decimal,
+    ) -> Result<FractionalMoney, Error> {
+        if scalar.is_zero() {
+            return Err(Error::DivisionByZero);
+        }
+        match value.amount().checked_div(scalar) {
+            Some(amount) => self.finish(amount, value.currency()),
+            None => self.overflowed(
+                value.currency(),
+                value.is_negative() != scalar.is_sign_negative(),
+            ),
+        }
+    }
+
+    /// Sums monetary values. If `values` is empty, then the special `ZeroNone` currency will be
+    /// the result. Returns an error if the currencies do not match.
+    pub fn sum<'a, I>(&self, values: I) -> Result<FractionalMoney, Error>
+    where
+        I: IntoIterator<Item = &'a FractionalMoney>,
+    {
+        values
+            .into_iter()
+            .try_fold(FractionalMoney::zero(), |acc, v| self.add(&acc, v))
+    }
+
+    /// Rounds a monetary value according to `rounding` and `cash_rounding`. Returns an `Overflow`
+    /// error if the value cannot be rounded to a cash denomination.
+    pub fn round(&self, value: &FractionalMoney) -> Result<Money, Error> {
+        if !self.cash_rounding {
+            return Ok(value.round_with(self.rounding));
+        }
+        let currency = value.currency();
+        let increment = Decimal::new(currency.cash_increment().into(), currency.max_precision());
+        let amount = value
+            .amount()
+            .checked_div(increment)
+            .map(|increments| increments.round_dp_with_strategy(0, self.rounding.strategy()))
+            .and_then(|increments| increments.checked_mul(increment))
+            .ok_or(Error::Overflow)?;
+        Ok(FractionalMoney::new(amount, currency)?.round_with(self.rounding))
+    }
+
+    fn finish(&self, amount: Decimal, currency: Currency) -> Result<FractionalMoney, Error> {
+        Ok(self.limit_scale(FractionalMoney::new(amount, currency)?))
+    }
+
+    fn limit_scale(&self, value: FractionalMoney) -> FractionalMoney {
+        if value.amount().scale() <= self.max_scale {
+            return value;
+        }
+        let amount = value
+            .amount()
+            .round_dp_with_strategy(self.max_scale, self.rounding.strategy());
+        FractionalMoney::new(amount, value.currency()).unwrap()
+    }
+
+    fn overflowed(&self, currency: Currency, negative: bool) -> Result<FractionalMoney, Error> {
+        match self.overflow {
+            OverflowPolicy::Error => Err(Error::Overflow),
+            OverflowPolicy::Saturate => {
+                let amount = if negative { Decimal::MIN } else { Decimal::MAX };
+                FractionalMoney::new(amount, currency)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::{cad, usd};
+    use anyhow::Result;
+    use expecting::*;
+    use rust_decimal_macros::dec;
+
+    fn usd(d: &str) -> FractionalMoney {
+        FractionalMoney::new(Decimal::from_str_exact(d).unwrap(), Currency::USD).unwrap()
+    }
+    fn cad(d: &str) -> FractionalMoney {
+        FractionalMoney::new(Decimal::from_str_exact(d).unwrap(), Currency::CAD).unwrap()
+    }
+
+    #[test]
+    fn default__matches_fractional_money() -> Result<()> {
+        let ctx = MoneyContext::default();
+        expect_eq!(
+            expect_ok!(ctx.divide(&usd("1"), dec!(3))),
+            usd("1") / dec!(3)
+        );
+        expect_eq!(
+            expect_ok!(ctx.multiply(&usd("2.25"), dec!(1.25))),
+            usd("2.25") * dec!(1.25)
+        );
+        expect_eq!(
+            expect_ok!(ctx.add(&usd("1"), &usd("2.12345"))),
+            usd("3.12345")
+        );
+        expect_eq!(ctx.round(&usd("1.005"))?, usd("1.005").round());
+        Ok(())
+    }
+
+    #[test]
+    fn max_scale__limits_intermediate_results() -> Result<()> {
+        let ctx = MoneyContext {
+            max_scale: 4,
+            ..Default::default()
+        };
+        let third = expect_ok!(ctx.divide(&usd("1"), dec!(3)));
+        expect_eq!(third.amount().to_string(), "0.3333");
+        expect_eq!(
+            expect_ok!(ctx.multiply(&usd("1.11111"), dec!(1))),
+            usd("1.1111")
+        );
+        expect_eq!(expect_ok!(ctx.add(&usd("0.00005"), &usd("0"))), usd("0"));
+        expect_eq!(
+            expect_ok!(ctx.subtract(&usd("0.00015"), &usd("0"))),
+            usd("0.0002")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rounding__is_used_for_intermediate_and_final_results() -> Result<()> {
+        let ctx = MoneyContext {
+            max_scale: 3,
+            rounding: RoundingMode::ToPositiveInfinity,
+            ..Default::default()
+        };
+        let third = expect_ok!(ctx.divide(&usd("1"), dec!(3)));
+        expect_eq!(third, usd("0.334"));
+        expect_eq!(ctx.round(&third)?, usd!(0.34));
+        Ok(())
+    }
+
+    #[test]
+    fn divide__by_zero__returns_err() -> Result<()> {
+        let ctx = MoneyContext::default();
+        expect_eq!(
+            expect_err!(ctx.divide(&usd("1"), dec!(0))),
+            Error::DivisionByZero
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn add__mismatched_currency__returns_err() -> Result<()> {
+        let ctx = MoneyContext::default();
+        expect_eq!(
+            expect_err!(ctx.add(&usd("1"), &cad("1"))),
+            Error::MismatchedCurrency
+        );
+        expect_eq!(
+            expect_err!(ctx.subtract(&usd("1"), &cad("1"))),
+            Error::MismatchedCurrency
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn overflow__error_policy__returns_err() -> Result<()> {
+        let ctx = MoneyContext::default();
+        let max = FractionalMoney::new(Decimal::MAX, Currency::USD)?;
+        expect_eq!(expect_err!(ctx.add(&max, &max)), Error::Overflow);
+        expect_eq!(expect_err!(ctx.multiply(&max, dec!(2))), Error::Overflow);
+        Ok(())
+    }
+
+    #[test]
+    fn overflow__saturate_policy__clamps() -> Result<()> {
+        let ctx = MoneyContext {
+            overflow: OverflowPolicy::Saturate,
+            ..Default::default()
+        };
+        let max = FractionalMoney::new(Decimal::MAX, Currency::USD)?;
+        expect_eq!(expect_ok!(ctx.add(&max, &max)), max);
+        expect_eq!(expect_ok!(ctx.multiply(&max, dec!(-2))), -max);
+        expect_eq!(expect_ok!(ctx.subtract(&-max, &max)), -max);
+        expect_eq!(expect_ok!(ctx.divide(&max, dec!(0.5))), max);
+        Ok(())
+    }
+
+    #[test]
+    fn sum() -> Result<()> {
+        let ctx = MoneyContext {
+            max_scale: 2,
+            ..Default::default()
+        };
+        let values = [usd("0.333"), usd("0.333"), usd("0.333")];
+        expect_eq!(expect_ok!(ctx.sum(&values)), usd("0.99"));
+        expect_eq!(expect_ok!(ctx.sum(&[])), FractionalMoney::zero());
+        Ok(())
+    }
+
+    #[test]
+    fn round__cash_rounding() -> Result<()> {
+        let ctx = MoneyContext {
+            cash_rounding: true,
+            ..Default::default()
+        };
+        expect_eq!(ctx.round(&cad("1.02"))?, cad!(1.00));
+        expect_eq!(ctx.round(&cad("1.03"))?, cad!(1.05));
+        expect_eq!(ctx.round(&cad("1.074"))?, cad!(1.05));
+        expect_eq!(ctx.round(&cad("-1.08"))?, cad!(-1.10));
+        expect_eq!(ctx.round(&cad("1.025"))?, cad!(1.00));
+        expect_eq!(ctx.round(&usd("1.026"))?, usd!(1.03));
+        expect_eq!(ctx.round(&FractionalMoney::zero())?, Money::zero());
+        expect_eq!(ctx.round(&cad("1.03"))?.to_string(), "1.05 CAD");
+        let max = FractionalMoney::new(Decimal::MAX, Currency::CAD)?;
+        expect_eq!(expect_err!(ctx.round(&max)), Error::Overflow);
+        Ok(())
+    }
+}
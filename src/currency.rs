@@ -1,4 +1,5 @@
 use crate::Error;
+use std::str::FromStr;
 
 /// Supported currencies, identified by their ISO 4217 code.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -21,6 +22,30 @@ impl Currency {
             Currency::CAD => 2,
         }
     }
+
+    /// The smallest denomination used for cash payments, in minor units. For example, the penny
+    /// has been withdrawn from circulation in Canada, so cash amounts in CAD are rounded to the
+    /// nearest 5 cents.
+    pub fn cash_increment(&self) -> u32 {
+        match self {
+            Currency::ZeroNone => 1,
+            Currency::USD => 1,
+            Currency::CAD => 5,
+        }
+    }
+}
+
+/// Parses an ISO 4217 code, e.g., "USD". The special `ZeroNone` currency cannot be parsed.
+impl FromStr for Currency {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "USD" => Ok(Currency::USD),
+            "CAD" => Ok(Currency::CAD),
+            _ => Err(Error::UnknownCurrency(s.to_string())),
+        }
+    }
 }
 
 /// Returns the result of operating on two currencies. Generally, they should be the same, or else
@@ -47,6 +72,28 @@ mod tests {
     use anyhow::Result;
     use expecting::*;
 
+    #[test]
+    fn cash_increment() -> Result<()> {
+        expect_eq!(Currency::USD.cash_increment(), 1);
+        expect_eq!(Currency::CAD.cash_increment(), 5);
+        Ok(())
+    }
+
+    #[test]
+    fn from_str() -> Result<()> {
+        expect_eq!(expect_ok!("USD".parse::<Currency>()), Currency::USD);
+        expect_eq!(expect_ok!("CAD".parse::<Currency>()), Currency::CAD);
+        Ok(())
+    }
+
+    #[test]
+    fn from_str__unknown__returns_err() -> Result<()> {
+        let e = expect_err!("usd".parse::<Currency>());
+        expect_eq!(e, Error::UnknownCurrency("usd".to_string()));
+        expect_err!("ZeroNone".parse::<Currency>());
+        Ok(())
+    }
+
     #[test]
     fn combine_currency__same__returns_same() -> Result<()> {
         let combined = expect_ok!(combine_currency(Currency::USD, Currency::USD));
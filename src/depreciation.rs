@@ -0,0 +1,359 @@
+use crate::currency;
+use crate::error::Error;
+use crate::fractional_money::FractionalMoney;
+use crate::money::Money;
+use crate::rounding::{RoundingAccumulator, RoundingMode};
+use rust_decimal::Decimal;
+
+/// An asset to be depreciated from its cost down to its salvage value.
+///
+/// Every method produces a schedule whose depreciation amounts, each rounded to the minor unit of
+/// the currency, sum exactly to the cost minus the salvage value. Rounding differences are carried
+/// from one period to the next, and the final period absorbs whatever remains.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Asset {
+    cost: Money,
+    salvage: Money,
+}
+
+impl Asset {
+    /// Returns an error if the currencies do not match, or unless `0 <= salvage <= cost`.
+    pub fn new(cost: Money, salvage: Money) -> Result<Self, Error> {
+        currency::combine_currency(cost.currency(), salvage.currency())?;
+        if salvage.is_negative() || salvage > cost {
+            return Err(Error::InvalidArgument(format!(
+                "expected a salvage value between zero and the cost of {cost}, but got {salvage}"
+            )));
+        }
+        Ok(Self { cost, salvage })
+    }
+
+    pub fn cost(&self) -> Money {
+        self.cost
+    }
+
+    pub fn salvage(&self) -> Money {
+        self.salvage
+    }
+
+    /// The total amount to depreciate, i.e., the cost minus the salvage value.
+    pub fn depreciable_amount(&self) -> Money {
+        self.cost - self.salvage
+    }
+
+    /// Depreciates the same amount each period.
+    pub fn straight_line(&self, periods: u32) -> Result<DepreciationSchedule, Error> {
+        check_periods(periods)?;
+        let weights = vec![Decimal::ONE; periods as usize];
+        self.proportional(&weights)
+    }
+
+    /// Depreciates in proportion to the remaining life, i.e., `n / (1 + 2 + ... + n)` of the
+    /// depreciable amount in the first of `n` periods, then `(n - 1) / (1 + 2 + ... + n)`, etc.
+    pub fn sum_of_years_digits(&self, periods: u32) -> Result<DepreciationSchedule, Error> {
+        check_periods(periods)?;
+        let weights: Vec<Decimal> = (1..=periods).rev().map(Decimal::from).collect();
+        self.proportional(&weights)
+    }
+
+    /// Depreciates in proportion to the units produced in each period, e.g., machine hours.
+    /// There is one period per element of `units`, which together make up the asset's whole
+    /// useful life.
+    pub fn units_of_production(&self, units: &[Decimal]) -> Result<DepreciationSchedule, Error> {
+        check_periods(units.len() as u32)?;
+        if units.iter().any(|u| u.is_sign_negative()) || units.iter().all(|u| u.is_zero()) {
+            return Err(Error::InvalidArgument(
+                "expected non-negative units, with at least one period of production".to_string(),
+            ));
+        }
+        self.proportional(units)
+    }
+
+    /// Depreciates the book value by `factor` times the straight-line rate each period, e.g.,
+    /// a factor of 1.5 for 150% declining balance, without going below the salvage value. The
+    /// final period depreciates the remaining book value down to the salvage value.
+    pub fn declining_balance(
+        &self,
+        periods: u32,
+        factor: Decimal,
+    ) -> Result<DepreciationSchedule, Error> {
+        check_periods(periods)?;
+        if !factor.is_sign_positive() || factor.is_zero() {
+            return Err(Error::InvalidArgument(format!(
+                "expected a positive factor, but got {factor}"
+            )));
+        }
+        let rate = factor / Decimal::from(periods);
+        self.diminishing(periods, |_, book_value| book_value * rate)
+    }
+
+    /// Declining balance depreciation at twice the straight-line rate.
+    pub fn double_declining_balance(&self, periods: u32) -> Result<DepreciationSchedule, Error> {
+        self.declining_balance(periods, Decimal::TWO)
+    }
+
+    /// Canadian capital cost allowance (CCA) for an asset class with the given `rate`, e.g.,
+    /// `dec!(0.2)` for class 8, under the half-year rule: only half the rate may be claimed in
+    /// the year of acquisition. The final period claims the remaining undepreciated capital cost
+    /// down to the salvage value, i.e., a terminal loss on disposal.
+    pub fn cca_half_year(
+        &self,
+        periods: u32,
+        rate: Decimal,
+    ) -> Result<DepreciationSchedule, Error> {
+        check_periods(periods)?;
+        if !rate.is_sign_positive() || rate.is_zero() || rate > Decimal::ONE {
+            return Err(Error::InvalidArgument(format!(
+                "expected a rate greater than zero and at most one, but got {rate}"
+            )));
+        }
+        self.diminishing(periods, |number, ucc| {
+            if number == 1 {
+                ucc * (rate / Decimal::TWO)
+            } else {
+                ucc * rate
+            }
+        })
+    }
+
+    /// Produces a schedule which depreciates the depreciable amount in proportion to `weights`.
+    fn proportional(&self, weights: &[Decimal]) -> Result<DepreciationSchedule, Error> {
+        let total_weight: Decimal = weights.iter().sum();
+        let amount = FractionalMoney::from(self.depreciable_amount());
+        let mut accumulator = RoundingAccumulator::new(RoundingMode::MidpointNearestEven);
+        let mut depreciation = vec![];
+        for weight in &weights[..weights.len() - 1] {
+            let line = accumulator.push(&(amount * (*weight / total_weight)))?;
+            depreciation.push(line.amount);
+        }
+        let remaining = self
+            .depreciable_amount()
+            .try_subtract(&accumulator.rounded_total())?;
+        depreciation.push(remaining);
+        self.schedule(depreciation)
+    }
+
+    /// Produces a schedule where each period's depreciation is calculated from the book value by
+    /// `f`, given the period number and the book value.
+    fn diminishing(
+        &self,
+        periods: u32,
+        f: impl Fn(u32, Money) -> FractionalMoney,
+    ) -> Result<DepreciationSchedule, Error> {
+        let mut book_value = self.cost;
+        let mut depreciation = vec![];
+        for number in 1..=periods {
+            let remaining = book_value.try_subtract(&self.salvage)?;
+            let amount = if number == periods {
+                remaining
+            } else {
+                f(number, book_value)
+                    .round_with(RoundingMode::MidpointNearestEven)
+                    .min(remaining)
+            };
+            book_value = book_value.try_subtract(&amount)?;
+            depreciation.push(amount);
+        }
+        self.schedule(depreciation)
+    }
+
+    fn schedule(&self, depreciation: Vec<Money>) -> Result<DepreciationSchedule, Error> {
+        let mut accumulated = Money::new(Decimal::ZERO, self.cost.currency())?;
+        let mut periods = vec![];
+        for (i, depreciation) in depreciation.into_iter().enumerate() {
+            accumulated = accumulated.try_add(&depreciation)?;
+            periods.push(DepreciationPeriod {
+                number: i as u32 + 1,
+                depreciation,
+                accumulated,
+                book_value: self.cost.try_subtract(&accumulated)?,
+            });
+        }
+        Ok(DepreciationSchedule { periods })
+    }
+}
+
+fn check_periods(periods: u32) -> Result<(), Error> {
+    if periods == 0 {
+        return Err(Error::InvalidArgument(
+            "expected at least one period".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// A single period of a depreciation schedule.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DepreciationPeriod {
+    /// The period number, counting from 1.
+    pub number: u32,
+    /// The depreciation for this period.
+    pub depreciation: Money,
+    /// The total depreciation up to and including this period.
+    pub accumulated: Money,
+    /// The cost minus the accumulated depreciation.
+    pub book_value: Money,
+}
+
+/// A depreciation schedule, i.e., the depreciation of an asset in each period of its useful life.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DepreciationSchedule {
+    periods: Vec<DepreciationPeriod>,
+}
+
+impl DepreciationSchedule {
+    pub fn periods(&self) -> &[DepreciationPeriod] {
+        &self.periods
+    }
+
+    /// The depreciation amounts for each period.
+    pub fn amounts(&self) -> Vec<Money> {
+        self.periods.iter().map(|p| p.depreciation).collect()
+    }
+
+    /// The total depreciation, which is always the cost minus the salvage value.
+    pub fn total(&self) -> Money {
+        self.periods.iter().map(|p| p.depreciation).sum()
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::{cad, usd};
+    use anyhow::Result;
+    use expecting::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn straight_line() -> Result<()> {
+        let asset = Asset::new(usd!(1000), usd!(100))?;
+        let schedule = expect_ok!(asset.straight_line(3));
+        expect_eq!(schedule.amounts(), vec![usd!(300); 3]);
+
+        let asset = Asset::new(usd!(1000), usd!(0))?;
+        let schedule = expect_ok!(asset.straight_line(3));
+        expect_eq!(
+            schedule.amounts(),
+            vec![usd!(333.33), usd!(333.34), usd!(333.33)]
+        );
+        expect_eq!(schedule.total(), usd!(1000));
+        Ok(())
+    }
+
+    #[test]
+    fn straight_line__periods() -> Result<()> {
+        let asset = Asset::new(usd!(1000), usd!(100))?;
+        let schedule = asset.straight_line(2)?;
+        expect_eq!(
+            schedule.periods()[0],
+            DepreciationPeriod {
+                number: 1,
+                depreciation: usd!(450),
+                accumulated: usd!(450),
+                book_value: usd!(550),
+            }
+        );
+        expect_eq!(schedule.periods()[1].book_value, usd!(100));
+        Ok(())
+    }
+
+    #[test]
+    fn sum_of_years_digits() -> Result<()> {
+        let asset = Asset::new(usd!(15000), usd!(0))?;
+        expect_eq!(
+            asset.sum_of_years_digits(5)?.amounts(),
+            vec![usd!(5000), usd!(4000), usd!(3000), usd!(2000), usd!(1000)]
+        );
+
+        let asset = Asset::new(usd!(1000), usd!(1))?;
+        let schedule = asset.sum_of_years_digits(7)?;
+        expect_eq!(schedule.total(), usd!(999));
+        Ok(())
+    }
+
+    #[test]
+    fn units_of_production() -> Result<()> {
+        let asset = Asset::new(usd!(10000), usd!(1000))?;
+        let schedule = asset.units_of_production(&[dec!(1000), dec!(0), dec!(2000), dec!(3000)])?;
+        expect_eq!(
+            schedule.amounts(),
+            vec![usd!(1500), usd!(0), usd!(3000), usd!(4500)]
+        );
+        expect_err!(asset.units_of_production(&[]));
+        expect_err!(asset.units_of_production(&[dec!(0), dec!(0)]));
+        expect_err!(asset.units_of_production(&[dec!(1), dec!(-1)]));
+        Ok(())
+    }
+
+    #[test]
+    fn double_declining_balance() -> Result<()> {
+        let asset = Asset::new(usd!(10000), usd!(1000))?;
+        let schedule = asset.double_declining_balance(5)?;
+        expect_eq!(
+            schedule.amounts(),
+            vec![usd!(4000), usd!(2400), usd!(1440), usd!(864), usd!(296)]
+        );
+        expect_eq!(schedule.periods()[4].book_value, usd!(1000));
+        Ok(())
+    }
+
+    #[test]
+    fn declining_balance__stops_at_salvage() -> Result<()> {
+        let asset = Asset::new(usd!(1000), usd!(500))?;
+        let schedule = asset.declining_balance(4, dec!(1.5))?;
+        // 37.5% of 1000, then the remaining 125.
+        expect_eq!(
+            schedule.amounts(),
+            vec![usd!(375), usd!(125), usd!(0), usd!(0)]
+        );
+        expect_err!(asset.declining_balance(4, dec!(0)));
+        Ok(())
+    }
+
+    #[test]
+    fn declining_balance__rounding() -> Result<()> {
+        let asset = Asset::new(usd!(1000), usd!(0))?;
+        let schedule = asset.declining_balance(3, dec!(1.5))?;
+        expect_eq!(schedule.amounts(), vec![usd!(500), usd!(250), usd!(250)]);
+        let schedule = asset.declining_balance(7, dec!(1))?;
+        expect_eq!(schedule.periods()[0].depreciation, usd!(142.86));
+        expect_eq!(schedule.periods()[1].depreciation, usd!(122.45));
+        expect_eq!(schedule.total(), usd!(1000));
+        Ok(())
+    }
+
+    #[test]
+    fn cca_half_year() -> Result<()> {
+        let asset = Asset::new(cad!(10000), cad!(0))?;
+        let schedule = asset.cca_half_year(4, dec!(0.2))?;
+        expect_eq!(
+            schedule.amounts(),
+            vec![cad!(1000), cad!(1800), cad!(1440), cad!(5760)]
+        );
+        expect_err!(asset.cca_half_year(4, dec!(1.5)));
+        Ok(())
+    }
+
+    #[test]
+    fn new__invalid_salvage__returns_err() -> Result<()> {
+        expect_err!(Asset::new(usd!(100), usd!(101)));
+        expect_err!(Asset::new(usd!(100), usd!(-1)));
+        expect_eq!(
+            expect_err!(Asset::new(usd!(100), cad!(1))),
+            Error::MismatchedCurrency
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn zero_periods__returns_err() -> Result<()> {
+        let asset = Asset::new(usd!(100), usd!(0))?;
+        expect_err!(asset.straight_line(0));
+        expect_err!(asset.sum_of_years_digits(0));
+        expect_err!(asset.double_declining_balance(0));
+        expect_err!(asset.cca_half_year(0, dec!(0.2)));
+        Ok(())
+    }
+}
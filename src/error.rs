@@ -10,8 +10,35 @@ pub enum Error {
     ZeroCurrencyWithNonZeroAmount,
     /// A mathematical operation was attempted on monetary values of different currencies.
     MismatchedCurrency,
+    /// A mathematical operation was attempted on quantities or prices of different units.
+    MismatchedUnit,
     /// There was an overflow error in the underlying Decimal library.
     Overflow,
+    /// A division (or remainder) by a zero value was attempted.
+    DivisionByZero,
+    /// The given string is not the code of a supported currency.
+    UnknownCurrency(String),
+    /// A formula could not be parsed or evaluated. `position` is the zero-based index of the
+    /// character where the problem was found.
+    Expression { position: usize, message: String },
+    /// A text format, e.g., a journal file, could not be parsed. `line` and `column` are the
+    /// one-based position of the character where the problem was found.
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// An argument is outside the range accepted by the function, e.g., an end date before the
+    /// start date.
+    InvalidArgument(String),
+    /// An iterative calculation, e.g., of an internal rate of return, did not find a solution.
+    NoConvergence,
+    /// The debits of a journal entry do not equal its credits.
+    UnbalancedEntry(String),
+    /// A journal entry refers to an account which is not in the ledger.
+    UnknownAccount(String),
+    /// An account with the same name is already in the ledger.
+    DuplicateAccount(String),
 }
 
 impl fmt::Display for Error {
@@ -29,12 +56,49 @@ impl fmt::Display for Error {
                     "A mathematical operation was attempted on values of different currencies"
                 )
             }
+            Self::MismatchedUnit => {
+                write!(
+                    f,
+                    "A mathematical operation was attempted on values of different units"
+                )
+            }
             Self::Overflow => {
                 write!(
                     f,
                     "There was an overflow error in the underlying Decimal library."
                 )
             }
+            Self::DivisionByZero => {
+                write!(f, "Attempted to divide by zero.")
+            }
+            Self::UnknownCurrency(code) => {
+                write!(f, "Unknown currency: {code}")
+            }
+            Self::Expression { position, message } => {
+                write!(f, "Invalid expression at position {position}: {message}")
+            }
+            Self::Parse {
+                line,
+                column,
+                message,
+            } => {
+                write!(f, "Parse error at line {line}, column {column}: {message}")
+            }
+            Self::InvalidArgument(details) => {
+                write!(f, "Invalid argument: {details}")
+            }
+            Self::NoConvergence => {
+                write!(f, "The calculation did not converge to a solution.")
+            }
+            Self::UnbalancedEntry(details) => {
+                write!(f, "Unbalanced journal entry: {details}")
+            }
+            Self::UnknownAccount(name) => {
+                write!(f, "Unknown account: {name}")
+            }
+            Self::DuplicateAccount(name) => {
+                write!(f, "Duplicate account: {name}")
+            }
         }
     }
 }
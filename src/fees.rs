@@ -0,0 +1,369 @@
+use crate::brackets::BracketSchedule;
+use crate::currency;
+use crate::currency::Currency;
+use crate::error::Error;
+use crate::fractional_money::FractionalMoney;
+use crate::money::Money;
+use crate::rounding::RoundingMode;
+use rust_decimal::Decimal;
+
+/// The maximum number of times `FeeSchedule::gross_up()` doubles its search range before giving
+/// up, e.g., because the fee grows as fast as the amount.
+const MAX_DOUBLINGS: u32 = 64;
+
+/// A part of a `FeeSchedule`. The components' exact amounts are summed before rounding.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FeeComponent {
+    /// A fraction of the amount, e.g., `FeeComponent::Percentage(dec!(0.029))` for 2.9%.
+    Percentage(Decimal),
+    /// A fixed amount per charge.
+    Fixed(Money),
+    /// A fee determined by brackets of the amount. Its total is rounded by the schedule itself.
+    Tiered(BracketSchedule),
+}
+
+/// The fees charged on a payment, e.g., 2.9% + 0.30 USD for a card payment, optionally limited to
+/// a minimum and maximum.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FeeSchedule {
+    components: Vec<FeeComponent>,
+    minimum: Option<Money>,
+    maximum: Option<Money>,
+    rounding: RoundingMode,
+}
+
+impl FeeSchedule {
+    /// Creates a fee schedule with no components, i.e., no fees, using the default rounding
+    /// method.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_component(mut self, component: FeeComponent) -> Self {
+        self.components.push(component);
+        self
+    }
+
+    pub fn with_percentage(self, rate: Decimal) -> Self {
+        self.with_component(FeeComponent::Percentage(rate))
+    }
+
+    pub fn with_fixed(self, amount: Money) -> Self {
+        self.with_component(FeeComponent::Fixed(amount))
+    }
+
+    pub fn with_tiered(self, brackets: BracketSchedule) -> Self {
+        self.with_component(FeeComponent::Tiered(brackets))
+    }
+
+    pub fn with_minimum(mut self, minimum: Money) -> Self {
+        self.minimum = Some(minimum);
+        self
+    }
+
+    pub fn with_maximum(mut self, maximum: Money) -> Self {
+        self.maximum = Some(maximum);
+        self
+    }
+
+    pub fn with_rounding(mut self, rounding: RoundingMode) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    pub fn components(&self) -> &[FeeComponent] {
+        &self.components
+    }
+
+    /// Calculates the fee on an amount: the components are summed exactly, rounded, and then
+    /// limited to the minimum and maximum.
+    ///
+    /// Returns an error if the amount or a percentage is negative, the minimum exceeds the
+    /// maximum, or the currencies do not match.
+    pub fn fee_for(&self, amount: &Money) -> Result<Money, Error> {
+        if amount.is_negative() {
+            return Err(Error::InvalidArgument(format!(
+                "expected a non-negative amount, but got {amount}"
+            )));
+        }
+        let currency = currency::combine_currency(self.currency()?, amount.currency())?;
+
+        let mut exact = FractionalMoney::new(Decimal::ZERO, currency)?;
+        for component in &self.components {
+            let fee = match component {
+                FeeComponent::Percentage(rate) => {
+                    if rate.is_sign_negative() {
+                        return Err(Error::InvalidArgument(format!(
+                            "expected a non-negative percentage, but got {rate}"
+                        )));
+                    }
+                    let fee = amount.amount().checked_mul(*rate).ok_or(Error::Overflow)?;
+                    FractionalMoney::new(fee, currency)?
+                }
+                FeeComponent::Fixed(fee) => (*fee).into(),
+                FeeComponent::Tiered(brackets) => brackets.calculate(*amount)?.total.into(),
+            };
+            exact = exact.try_add(&fee)?;
+        }
+
+        let mut fee = exact.round_with(self.rounding);
+        if let (Some(minimum), Some(maximum)) = (self.minimum, self.maximum) {
+            if minimum > maximum {
+                return Err(Error::InvalidArgument(format!(
+                    "the minimum fee {minimum} exceeds the maximum fee {maximum}"
+                )));
+            }
+        }
+        if let Some(minimum) = self.minimum {
+            if fee < minimum {
+                fee = minimum;
+            }
+        }
+        if let Some(maximum) = self.maximum {
+            if fee > maximum {
+                fee = maximum;
+            }
+        }
+        Ok(fee)
+    }
+
+    /// Finds the smallest gross amount such that the gross amount minus its `fee_for()` is exactly
+    /// `net`, e.g., the amount to charge a customer so that 100.00 USD is received after card
+    /// fees.
+    ///
+    /// Because the fee is rounded, dividing by one minus the percentage is not always exact, so
+    /// this searches the candidate amounts in minor units instead. This assumes the net amount
+    /// never decreases as the gross amount increases, which holds unless the percentages sum to
+    /// 100% or more or there are all-units tiers. Returns an error if no gross amount results in
+    /// exactly `net`.
+    pub fn gross_up(&self, net: &Money) -> Result<Money, Error> {
+        if net.is_negative() {
+            return Err(Error::InvalidArgument(format!(
+                "expected a non-negative amount, but got {net}"
+            )));
+        }
+        let currency = currency::combine_currency(self.currency()?, net.currency())?;
+        let target = net.minor_units();
+        let net_of = |units: i128| -> Result<i128, Error> {
+            let gross = Money::from_minor_units(units, currency)?;
+            Ok(gross.try_subtract(&self.fee_for(&gross)?)?.minor_units())
+        };
+        let no_solution =
+            || Error::InvalidArgument(format!("no gross amount results in a net amount of {net}"));
+
+        // Fees are non-negative, so the gross amount is at least the net amount.
+        let mut low = target;
+        match net_of(low)? {
+            low_net if low_net == target => return Money::from_minor_units(low, currency),
+            low_net if low_net > target => return Err(no_solution()),
+            _ => {}
+        }
+
+        // Find an upper bound, then search between the bounds, keeping net_of(low) < target and
+        // net_of(high) >= target.
+        let mut step = target.max(1);
+        let mut high = low.checked_add(step).ok_or(Error::Overflow)?;
+        let mut doublings = 0;
+        while net_of(high)? < target {
+            doublings += 1;
+            if doublings > MAX_DOUBLINGS {
+                return Err(Error::NoConvergence);
+            }
+            low = high;
+            step = step.checked_mul(2).ok_or(Error::Overflow)?;
+            high = high.checked_add(step).ok_or(Error::Overflow)?;
+        }
+        while high - low > 1 {
+            let middle = low + (high - low) / 2;
+            if net_of(middle)? >= target {
+                high = middle;
+            } else {
+                low = middle;
+            }
+        }
+
+        if net_of(high)? != target {
+            return Err(no_solution());
+        }
+        Money::from_minor_units(high, currency)
+    }
+
+    /// The currency of the fixed, tiered, minimum and maximum amounts, if any.
+    fn currency(&self) -> Result<Currency, Error> {
+        let mut currency = Currency::ZeroNone;
+        for component in &self.components {
+            let other = match component {
+                FeeComponent::Percentage(_) => continue,
+                FeeComponent::Fixed(amount) => amount.currency(),
+                FeeComponent::Tiered(brackets) => brackets.currency(),
+            };
+            currency = currency::combine_currency(currency, other)?;
+        }
+        for limit in [self.minimum, self.maximum].into_iter().flatten() {
+            currency = currency::combine_currency(currency, limit.currency())?;
+        }
+        Ok(currency)
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::brackets::{Bracket, BracketMode, Tier};
+    use crate::{cad, usd};
+    use anyhow::Result;
+    use expecting::*;
+    use rust_decimal_macros::dec;
+
+    fn card() -> FeeSchedule {
+        FeeSchedule::new()
+            .with_percentage(dec!(0.029))
+            .with_fixed(usd!(0.30))
+    }
+
+    #[test]
+    fn fee_for__percentage_and_fixed() -> Result<()> {
+        expect_eq!(card().fee_for(&usd!(100))?, usd!(3.20));
+        expect_eq!(card().fee_for(&usd!(10))?, usd!(0.59));
+        // 0.02871 + 0.30
+        expect_eq!(card().fee_for(&usd!(0.99))?, usd!(0.33));
+        expect_eq!(card().fee_for(&usd!(0))?, usd!(0.30));
+        expect_eq!(FeeSchedule::new().fee_for(&usd!(100))?, usd!(0));
+        Ok(())
+    }
+
+    #[test]
+    fn fee_for__rounds_the_sum() -> Result<()> {
+        // 0.125 + 0.125 = 0.25, whereas rounding each would give 0.12 + 0.12.
+        let fees = FeeSchedule::new()
+            .with_percentage(dec!(0.0125))
+            .with_percentage(dec!(0.0125));
+        expect_eq!(fees.fee_for(&usd!(10))?, usd!(0.25));
+
+        let fees = FeeSchedule::new()
+            .with_percentage(dec!(0.0125))
+            .with_rounding(RoundingMode::ToPositiveInfinity);
+        expect_eq!(fees.fee_for(&usd!(1))?, usd!(0.02));
+        Ok(())
+    }
+
+    #[test]
+    fn fee_for__minimum_and_maximum() -> Result<()> {
+        let fees = FeeSchedule::new()
+            .with_percentage(dec!(0.01))
+            .with_minimum(usd!(0.50))
+            .with_maximum(usd!(5));
+        expect_eq!(fees.fee_for(&usd!(10))?, usd!(0.50));
+        expect_eq!(fees.fee_for(&usd!(100))?, usd!(1));
+        expect_eq!(fees.fee_for(&usd!(1000))?, usd!(5));
+        Ok(())
+    }
+
+    #[test]
+    fn fee_for__tiered() -> Result<()> {
+        let tiers = BracketSchedule::new(
+            vec![
+                Bracket::new(cad!(0), Tier::Rate(dec!(0.03))),
+                Bracket::new(cad!(1000), Tier::Rate(dec!(0.02))),
+            ],
+            BracketMode::Graduated,
+        )?;
+        let fees = FeeSchedule::new().with_tiered(tiers).with_fixed(cad!(0.25));
+        expect_eq!(fees.fee_for(&cad!(500))?, cad!(15.25));
+        expect_eq!(fees.fee_for(&cad!(1500))?, cad!(40.25));
+        Ok(())
+    }
+
+    #[test]
+    fn fee_for__invalid__returns_err() -> Result<()> {
+        expect_err!(card().fee_for(&usd!(-1)));
+        expect_eq!(
+            expect_err!(card().fee_for(&cad!(1))),
+            Error::MismatchedCurrency
+        );
+        expect_err!(FeeSchedule::new()
+            .with_percentage(dec!(-0.01))
+            .fee_for(&usd!(1)));
+        expect_err!(FeeSchedule::new()
+            .with_minimum(usd!(2))
+            .with_maximum(usd!(1))
+            .fee_for(&usd!(1)));
+        expect_eq!(
+            expect_err!(FeeSchedule::new()
+                .with_fixed(usd!(1))
+                .with_minimum(cad!(1))
+                .fee_for(&usd!(1))),
+            Error::MismatchedCurrency
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fee_for__tiered_zero_first_threshold__mismatched_currency__returns_err() -> Result<()> {
+        let tiers = BracketSchedule::new(
+            vec![
+                Bracket::new(Money::zero(), Tier::Rate(dec!(0.01))),
+                Bracket::new(usd!(1000), Tier::Rate(dec!(0.02))),
+            ],
+            BracketMode::Graduated,
+        )?;
+        let fees = FeeSchedule::new().with_tiered(tiers).with_fixed(cad!(1));
+        expect_eq!(
+            expect_err!(fees.fee_for(&cad!(2000))),
+            Error::MismatchedCurrency
+        );
+        expect_eq!(
+            expect_err!(fees.gross_up(&cad!(2000))),
+            Error::MismatchedCurrency
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn gross_up__card() -> Result<()> {
+        // 103.30 - 3.30 = 100.00, whereas 103.29 - 3.30 = 99.99.
+        expect_eq!(card().gross_up(&usd!(100))?, usd!(103.30));
+        expect_eq!(card().gross_up(&usd!(0))?, usd!(0.31));
+        Ok(())
+    }
+
+    #[test]
+    fn gross_up__is_exact_and_smallest() -> Result<()> {
+        let schedules = [
+            card(),
+            FeeSchedule::new()
+                .with_percentage(dec!(0.0349))
+                .with_fixed(usd!(0.49)),
+            FeeSchedule::new()
+                .with_percentage(dec!(0.015))
+                .with_minimum(usd!(1))
+                .with_maximum(usd!(2)),
+        ];
+        for fees in schedules {
+            for cents in 0..2000 {
+                let net = Money::from_minor_units(cents * 7, Currency::USD)?;
+                let gross = fees.gross_up(&net)?;
+                expect_eq!(gross.try_subtract(&fees.fee_for(&gross)?)?, net);
+                let smaller = gross.try_subtract(&usd!(0.01))?;
+                if !smaller.is_negative() {
+                    let smaller_net = smaller.try_subtract(&fees.fee_for(&smaller)?)?;
+                    expect!(smaller_net < net);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn gross_up__no_solution__returns_err() -> Result<()> {
+        let fees = FeeSchedule::new().with_percentage(dec!(1));
+        expect_eq!(expect_err!(fees.gross_up(&usd!(1))), Error::NoConvergence);
+        expect_err!(card().gross_up(&usd!(-1)));
+        expect_eq!(
+            expect_err!(card().gross_up(&cad!(1))),
+            Error::MismatchedCurrency
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,719 @@
+use crate::currency;
+use crate::currency::Currency;
+use crate::error::Error;
+use crate::fractional_money::FractionalMoney;
+use crate::money::Money;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// A value in a `Formula`, i.e., the value of a literal, a variable or an intermediate result.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FormulaValue {
+    Money(FractionalMoney),
+    Number(Decimal),
+    /// A percentage as written, e.g., `Percent(dec!(10))` is 10%.
+    Percent(Decimal),
+}
+
+impl FormulaValue {
+    fn describe(&self) -> &'static str {
+        match self {
+            Self::Money(_) => "a monetary amount",
+            Self::Number(_) => "a number",
+            Self::Percent(_) => "a percentage",
+        }
+    }
+}
+
+impl From<FractionalMoney> for FormulaValue {
+    fn from(value: FractionalMoney) -> Self {
+        Self::Money(value)
+    }
+}
+
+impl From<Money> for FormulaValue {
+    fn from(value: Money) -> Self {
+        Self::Money(value.into())
+    }
+}
+
+impl From<Decimal> for FormulaValue {
+    fn from(value: Decimal) -> Self {
+        Self::Number(value)
+    }
+}
+
+/// A parsed arithmetic expression over monetary amounts, numbers and percentages, e.g.,
+/// `13.37 USD * 1.05 + fee - 10%`. It can be evaluated any number of times with different values
+/// for its variables.
+///
+/// The syntax is as follows:
+///  - Monetary amounts are written as a number followed by a currency code, e.g., `13.37 USD`.
+///    They may have more decimal places than the currency allows, e.g., `3.999 USD`.
+///  - Percentages are written as a number followed by `%`, e.g., `10%`.
+///  - Variables are names made of letters, digits and underscores, e.g., `unit_price`.
+///  - The operators are `+`, `-`, `*` and `/` with the usual precedence, unary `-`, and
+///    parentheses.
+///
+/// Adding (or subtracting) a percentage to an amount adds (or subtracts) that percentage of the
+/// amount, like a calculator: `200 USD - 10%` is `180 USD`. Otherwise, a percentage behaves like
+/// the equivalent fraction, e.g., `200 USD * 10%` is `20 USD`. Monetary amounts can be added to or
+/// subtracted from one another only if their currencies match, and dividing two amounts results
+/// in their ratio.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Formula {
+    source: String,
+    expr: Expr,
+}
+
+impl Formula {
+    /// Parses a formula. Returns an `Expression` error with the position of the problem if it
+    /// cannot be parsed.
+    pub fn parse(source: &str) -> Result<Self, Error> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            tokens,
+            next: 0,
+            end: source.chars().count(),
+        };
+        let expr = parser.parse_expr()?;
+        if let Some((token, position)) = parser.peek() {
+            return Err(error_at(*position, format!("unexpected {token}")));
+        }
+
+        Ok(Self {
+            source: source.to_string(),
+            expr,
+        })
+    }
+
+    /// The names of the variables used by the formula, in order of first appearance.
+    pub fn variables(&self) -> Vec<&str> {
+        let mut names = vec![];
+        collect_variables(&self.expr, &mut names);
+        names
+    }
+
+    /// Evaluates the formula with the given variable values. The formula must evaluate to a
+    /// monetary amount. Returns an `Expression` error with the position of the problem if, e.g., a
+    /// variable is missing, or amounts of different currencies are added together.
+    pub fn evaluate(
+        &self,
+        variables: &HashMap<String, FormulaValue>,
+    ) -> Result<FractionalMoney, Error> {
+        match evaluate(&self.expr, variables)? {
+            FormulaValue::Money(money) => Ok(money),
+            other => Err(error_at(
+                self.expr.position(),
+                format!(
+                    "expected the formula to result in a monetary amount, but it results in {}",
+                    other.describe()
+                ),
+            )),
+        }
+    }
+}
+
+impl FromStr for Formula {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl Display for Formula {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+/// Parses and evaluates a formula without variables, e.g., `13.37 USD * 1.05 + 2.00 USD - 10%`.
+/// See `Formula` for the syntax.
+pub fn evaluate_formula(source: &str) -> Result<FractionalMoney, Error> {
+    Formula::parse(source)?.evaluate(&HashMap::new())
+}
+
+fn error_at(position: usize, message: impl Into<String>) -> Error {
+    Error::Expression {
+        position,
+        message: message.into(),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Number(Decimal),
+    Ident(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    LeftParen,
+    RightParen,
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Number(n) => write!(f, "number '{n}'"),
+            Token::Ident(name) => write!(f, "'{name}'"),
+            Token::Plus => write!(f, "'+'"),
+            Token::Minus => write!(f, "'-'"),
+            Token::Star => write!(f, "'*'"),
+            Token::Slash => write!(f, "'/'"),
+            Token::Percent => write!(f, "'%'"),
+            Token::LeftParen => write!(f, "'('"),
+            Token::RightParen => write!(f, "')'"),
+        }
+    }
+}
+
+/// Splits the source into tokens, each paired with the position of its first character.
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, Error> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        i += 1;
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '*' => Token::Star,
+            '/' => Token::Slash,
+            '%' => Token::Percent,
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            c if c.is_ascii_digit() || c == '.' => {
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                let number = Decimal::from_str_exact(&text)
+                    .map_err(|_| error_at(start, format!("invalid number '{text}'")))?;
+                Token::Number(number)
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                Token::Ident(chars[start..i].iter().collect())
+            }
+            c => return Err(error_at(start, format!("unexpected character '{c}'"))),
+        };
+        tokens.push((token, start));
+    }
+    Ok(tokens)
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum BinaryOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Expr {
+    Literal(FormulaValue, usize),
+    Variable(String, usize),
+    /// A binary operation, along with the position of the operator.
+    Binary(BinaryOp, Box<Expr>, Box<Expr>, usize),
+    /// A negation, along with the position of the `-`.
+    Negate(Box<Expr>, usize),
+}
+
+impl Expr {
+    /// The position used to report errors in the expression's result.
+    fn position(&self) -> usize {
+        match self {
+            Expr::Literal(_, position)
+            | Expr::Variable(_, position)
+            | Expr::Binary(.., position)
+            | Expr::Negate(_, position) => *position,
+        }
+    }
+}
+
+/// A recursive descent parser for the grammar:
+///
+/// ```text
+/// expr    := term (('+' | '-') term)*
+/// term    := unary (('*' | '/') unary)*
+/// unary   := '-' unary | primary
+/// primary := NUMBER [CURRENCY | '%'] | IDENT | '(' expr ')'
+/// ```
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    next: usize,
+    /// The position just past the end of the source, for reporting a premature end.
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&(Token, usize)> {
+        self.tokens.get(self.next)
+    }
+
+    fn advance(&mut self) -> Result<(Token, usize), Error> {
+        let Some(token) = self.tokens.get(self.next).cloned() else {
+            return Err(error_at(self.end, "unexpected end of expression"));
+        };
+        self.next += 1;
+        Ok(token)
+    }
+
+    fn parse_expr(&mut self) -> Result<Expr, Error> {
+        let mut lhs = self.parse_term()?;
+        while let Some((token, position)) = self.peek().cloned() {
+            let op = match token {
+                Token::Plus => BinaryOp::Add,
+                Token::Minus => BinaryOp::Subtract,
+                _ => break,
+            };
+            self.next += 1;
+            let rhs = self.parse_term()?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs), position);
+        }
+        Ok(lhs)
+    }
+
+    fn parse_term(&mut self) -> Result<Expr, Error> {
+        let mut lhs = self.parse_unary()?;
+        while let Some((token, position)) = self.peek().cloned() {
+            let op = match token {
+                Token::Star => BinaryOp::Multiply,
+                Token::Slash => BinaryOp::Divide,
+                _ => break,
+            };
+            self.next += 1;
+            let rhs = self.parse_unary()?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs), position);
+        }
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, Error> {
+        if let Some(&(Token::Minus, position)) = self.peek() {
+            self.next += 1;
+            return Ok(Expr::Negate(Box::new(self.parse_unary()?), position));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, Error> {
+        let (token, position) = self.advance()?;
+        match token {
+            Token::Number(number) => match self.peek().cloned() {
+                Some((Token::Percent, _)) => {
+                    self.next += 1;
+                    Ok(Expr::Literal(FormulaValue::Percent(number), position))
+                }
+                Some((Token::Ident(code), code_position)) if is_currency_code(&code) => {
+                    self.next += 1;
+                    let currency = Currency::from_str(&code).map_err(|_| {
+                        error_at(code_position, format!("unknown currency '{code}'"))
+                    })?;
+                    let money = FractionalMoney::new(number, currency)
+                        .map_err(|e| error_at(position, e.to_string()))?;
+                    Ok(Expr::Literal(FormulaValue::Money(money), position))
+                }
+                _ => Ok(Expr::Literal(FormulaValue::Number(number), position)),
+            },
+            Token::Ident(name) => {
+                if Currency::from_str(&name).is_ok() {
+                    return Err(error_at(
+                        position,
+                        format!("currency '{name}' must follow an amount"),
+                    ));
+                }
+                Ok(Expr::Variable(name, position))
+            }
+            Token::LeftParen => {
+                let expr = self.parse_expr()?;
+                match self.advance() {
+                    Ok((Token::RightParen, _)) => Ok(expr),
+                    Ok((token, position)) => Err(error_at(
+                        position,
+                        format!("expected ')' but found {token}"),
+                    )),
+                    Err(_) => Err(error_at(self.end, "expected ')'")),
+                }
+            }
+            token => Err(error_at(position, format!("unexpected {token}"))),
+        }
+    }
+}
+
+/// Currency codes are three uppercase letters, e.g., "USD".
+fn is_currency_code(s: &str) -> bool {
+    s.len() == 3 && s.chars().all(|c| c.is_ascii_uppercase())
+}
+
+fn collect_variables<'a>(expr: &'a Expr, names: &mut Vec<&'a str>) {
+    match expr {
+        Expr::Literal(..) => {}
+        Expr::Variable(name, _) => {
+            if !names.contains(&name.as_str()) {
+                names.push(name);
+            }
+        }
+        Expr::Binary(_, lhs, rhs, _) => {
+            collect_variables(lhs, names);
+            collect_variables(rhs, names);
+        }
+        Expr::Negate(operand, _) => collect_variables(operand, names),
+    }
+}
+
+fn evaluate(expr: &Expr, variables: &HashMap<String, FormulaValue>) -> Result<FormulaValue, Error> {
+    match expr {
+        Expr::Literal(value, _) => Ok(*value),
+        Expr::Variable(name, position) => variables
+            .get(name)
+            .copied()
+            .ok_or_else(|| error_at(*position, format!("unknown variable '{name}'"))),
+        Expr::Binary(op, lhs, rhs, position) => {
+            let lhs = evaluate(lhs, variables)?;
+            let rhs = evaluate(rhs, variables)?;
+            apply(*op, lhs, rhs, *position)
+        }
+        Expr::Negate(operand, _) => Ok(match evaluate(operand, variables)? {
+            FormulaValue::Money(money) => FormulaValue::Money(-money),
+            FormulaValue::Number(n) => FormulaValue::Number(-n),
+            FormulaValue::Percent(p) => FormulaValue::Percent(-p),
+        }),
+    }
+}
+
+fn apply(
+    op: BinaryOp,
+    lhs: FormulaValue,
+    rhs: FormulaValue,
+    position: usize,
+) -> Result<FormulaValue, Error> {
+    use FormulaValue::{Money, Number, Percent};
+
+    let overflow = || error_at(position, "overflow");
+    let located = |e: Error| match e {
+        Error::Overflow => overflow(),
+        e => error_at(position, e.to_string()),
+    };
+    let fraction = |percent: Decimal| {
+        percent
+            .checked_div(Decimal::ONE_HUNDRED)
+            .ok_or_else(overflow)
+    };
+    let scale = |money: FractionalMoney, factor: Decimal| {
+        let amount = money.amount().checked_mul(factor).ok_or_else(overflow)?;
+        FractionalMoney::new(amount, money.currency()).map_err(located)
+    };
+    let divide = |a: Decimal, b: Decimal| {
+        if b.is_zero() {
+            return Err(error_at(position, "division by zero"));
+        }
+        a.checked_div(b).ok_or_else(overflow)
+    };
+    let check_currencies = |a: FractionalMoney, b: FractionalMoney| {
+        currency::combine_currency(a.currency(), b.currency()).map_err(|_| {
+            error_at(
+                position,
+                format!(
+                    "mismatched currencies {:?} and {:?}",
+                    a.currency(),
+                    b.currency()
+                ),
+            )
+        })
+    };
+    let unsupported = |verb: &str| {
+        error_at(
+            position,
+            format!("cannot {verb} {} and {}", lhs.describe(), rhs.describe()),
+        )
+    };
+
+    match op {
+        BinaryOp::Add | BinaryOp::Subtract => {
+            let sign = if op == BinaryOp::Add {
+                Decimal::ONE
+            } else {
+                Decimal::NEGATIVE_ONE
+            };
+            let combine = |a: Decimal, b: Decimal| a.checked_add(sign * b).ok_or_else(overflow);
+            Ok(match (lhs, rhs) {
+                (Money(a), Money(b)) => {
+                    check_currencies(a, b)?;
+                    let result = if op == BinaryOp::Add {
+                        a.try_add(&b)
+                    } else {
+                        a.try_subtract(&b)
+                    };
+                    Money(result.map_err(located)?)
+                }
+                (Money(a), Percent(p)) => Money(scale(a, combine(Decimal::ONE, fraction(p)?)?)?),
+                (Number(a), Number(b)) => Number(combine(a, b)?),
+                (Number(a), Percent(p)) => {
+                    let factor = combine(Decimal::ONE, fraction(p)?)?;
+                    Number(a.checked_mul(factor).ok_or_else(overflow)?)
+                }
+                (Percent(a), Percent(b)) => Percent(combine(a, b)?),
+                _ => {
+                    let verb = if op == BinaryOp::Add {
+                        "add"
+                    } else {
+                        "subtract"
+                    };
+                    return Err(unsupported(verb));
+                }
+            })
+        }
+        BinaryOp::Multiply => Ok(match (operand(lhs, fraction)?, operand(rhs, fraction)?) {
+            (Operand::Amount(_), Operand::Amount(_)) => return Err(unsupported("multiply")),
+            (Operand::Amount(a), Operand::Scalar(n)) | (Operand::Scalar(n), Operand::Amount(a)) => {
+                Money(scale(a, n)?)
+            }
+            (Operand::Scalar(a), Operand::Scalar(b)) => {
+                Number(a.checked_mul(b).ok_or_else(overflow)?)
+            }
+        }),
+        BinaryOp::Divide => Ok(match (operand(lhs, fraction)?, operand(rhs, fraction)?) {
+            (Operand::Amount(a), Operand::Amount(b)) => {
+                check_currencies(a, b)?;
+                Number(divide(a.amount(), b.amount())?)
+            }
+            (Operand::Amount(a), Operand::Scalar(n)) => {
+                let amount = divide(a.amount(), n)?;
+                Money(FractionalMoney::new(amount, a.currency()).map_err(located)?)
+            }
+            (Operand::Scalar(_), Operand::Amount(_)) => return Err(unsupported("divide")),
+            (Operand::Scalar(a), Operand::Scalar(b)) => Number(divide(a, b)?),
+        }),
+    }
+}
+
+/// An operand of multiplication or division: a monetary amount, or a number or percentage as a
+/// plain number.
+enum Operand {
+    Amount(FractionalMoney),
+    Scalar(Decimal),
+}
+
+/// Converts a value to an `Operand`, e.g., 10% is the scalar 0.1.
+fn operand(
+    value: FormulaValue,
+    fraction: impl Fn(Decimal) -> Result<Decimal, Error>,
+) -> Result<Operand, Error> {
+    Ok(match value {
+        FormulaValue::Money(money) => Operand::Amount(money),
+        FormulaValue::Number(n) => Operand::Scalar(n),
+        FormulaValue::Percent(p) => Operand::Scalar(fraction(p)?),
+    })
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::usd;
+    use anyhow::Result;
+    use expecting::*;
+    use rust_decimal_macros::dec;
+
+    fn usd(d: &str) -> FractionalMoney {
+        FractionalMoney::new(Decimal::from_str_exact(d).unwrap(), Currency::USD).unwrap()
+    }
+
+    fn position_of(e: Error) -> usize {
+        match e {
+            Error::Expression { position, .. } => position,
+            e => panic!("expected an Expression error, got {e:?}"),
+        }
+    }
+
+    #[test]
+    fn evaluate_formula__admin_adjustment() -> Result<()> {
+        // (13.37 * 1.05 + 2.00) * 0.9
+        let result = expect_ok!(evaluate_formula("13.37 USD * 1.05 + 2.00 USD - 10%"));
+        expect_eq!(result, usd("14.43465"));
+        expect_eq!(result.round(), usd!(14.43));
+        Ok(())
+    }
+
+    #[test]
+    fn evaluate_formula__precedence_and_parentheses() -> Result<()> {
+        expect_eq!(evaluate_formula("1 USD + 2 USD * 3")?, usd("7"));
+        expect_eq!(evaluate_formula("(1 USD + 2 USD) * 3")?, usd("9"));
+        expect_eq!(evaluate_formula("10 USD - 2 USD - 3 USD")?, usd("5"));
+        expect_eq!(evaluate_formula("12 USD / 2 / 3")?, usd("2"));
+        expect_eq!(evaluate_formula("-(1 USD) * -2")?, usd("2"));
+        expect_eq!(evaluate_formula("2 * (3 + 1) * 0.5 USD")?, usd("4.0"));
+        Ok(())
+    }
+
+    #[test]
+    fn evaluate_formula__percentages() -> Result<()> {
+        expect_eq!(evaluate_formula("200 USD - 10%")?, usd("180"));
+        expect_eq!(evaluate_formula("200 USD + 10% + 10%")?, usd("242"));
+        expect_eq!(evaluate_formula("200 USD * 15%")?, usd("30"));
+        expect_eq!(evaluate_formula("200 USD * (10% + 5%)")?, usd("30"));
+        expect_eq!(evaluate_formula("200 USD / 50%")?, usd("400"));
+        Ok(())
+    }
+
+    #[test]
+    fn evaluate_formula__ratio_of_amounts() -> Result<()> {
+        expect_eq!(evaluate_formula("10 USD / 4 USD * 1 USD")?, usd("2.5"));
+        Ok(())
+    }
+
+    #[test]
+    fn evaluate_formula__fractional_amount() -> Result<()> {
+        expect_eq!(evaluate_formula("3.999 USD * 10")?, usd("39.99"));
+        Ok(())
+    }
+
+    #[test]
+    fn evaluate_formula__mismatched_currency__returns_err() -> Result<()> {
+        let e = expect_err!(evaluate_formula("1 USD + 1 CAD"));
+        expect_eq!(
+            e,
+            Error::Expression {
+                position: 6,
+                message: "mismatched currencies USD and CAD".to_string()
+            }
+        );
+        expect_eq!(
+            position_of(expect_err!(evaluate_formula("1 USD / 1 CAD"))),
+            6
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn evaluate_formula__type_errors() -> Result<()> {
+        let e = expect_err!(evaluate_formula("1 USD * 2 USD"));
+        expect_eq!(
+            e,
+            Error::Expression {
+                position: 6,
+                message: "cannot multiply a monetary amount and a monetary amount".to_string()
+            }
+        );
+        expect_eq!(position_of(expect_err!(evaluate_formula("1 USD + 2"))), 6);
+        expect_eq!(position_of(expect_err!(evaluate_formula("2 / 1 USD"))), 2);
+        expect_eq!(position_of(expect_err!(evaluate_formula("2 * 3"))), 2);
+        expect_eq!(
+            position_of(expect_err!(evaluate_formula("(1 USD / 2 USD)"))),
+            7
+        );
+        expect_eq!(position_of(expect_err!(evaluate_formula("  -10%"))), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn evaluate_formula__division_by_zero__returns_err() -> Result<()> {
+        let e = expect_err!(evaluate_formula("10 USD / (1 - 1)"));
+        expect_eq!(
+            e,
+            Error::Expression {
+                position: 7,
+                message: "division by zero".to_string()
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn evaluate_formula__overflow__returns_err() -> Result<()> {
+        let e = expect_err!(evaluate_formula(
+            "79228162514264337593543950335 USD + 1 USD"
+        ));
+        expect_eq!(
+            e,
+            Error::Expression {
+                position: 34,
+                message: "overflow".to_string()
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse__syntax_errors() -> Result<()> {
+        let e = expect_err!(Formula::parse("1 USD +"));
+        expect_eq!(
+            e,
+            Error::Expression {
+                position: 7,
+                message: "unexpected end of expression".to_string()
+            }
+        );
+        expect_eq!(position_of(expect_err!(Formula::parse("1 USD $"))), 6);
+        expect_eq!(position_of(expect_err!(Formula::parse("(1 USD"))), 6);
+        expect_eq!(position_of(expect_err!(Formula::parse("1 USD 2"))), 6);
+        expect_eq!(position_of(expect_err!(Formula::parse("1.2.3 USD"))), 0);
+        expect_eq!(position_of(expect_err!(Formula::parse("1 USD * * 2"))), 8);
+        expect_eq!(position_of(expect_err!(Formula::parse("USD"))), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn parse__unknown_currency__returns_err() -> Result<()> {
+        let e = expect_err!(Formula::parse("1 EUR"));
+        expect_eq!(
+            e,
+            Error::Expression {
+                position: 2,
+                message: "unknown currency 'EUR'".to_string()
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn formula__variables() -> Result<()> {
+        let formula = expect_ok!(Formula::parse("price * quantity + fee - discount + fee"));
+        expect_eq!(
+            formula.variables(),
+            vec!["price", "quantity", "fee", "discount"]
+        );
+        expect_eq!(
+            formula.to_string(),
+            "price * quantity + fee - discount + fee"
+        );
+
+        let mut variables: HashMap<String, FormulaValue> = HashMap::new();
+        variables.insert("price".to_string(), usd!(1.50).into());
+        variables.insert("quantity".to_string(), dec!(4).into());
+        variables.insert("fee".to_string(), usd!(0.25).into());
+        variables.insert("discount".to_string(), FormulaValue::Percent(dec!(10)));
+        // ((1.50 * 4 + 0.25) * 0.9) + 0.25
+        expect_eq!(formula.evaluate(&variables)?, usd("5.875"));
+
+        variables.insert("quantity".to_string(), dec!(2).into());
+        expect_eq!(formula.evaluate(&variables)?, usd("3.175"));
+        Ok(())
+    }
+
+    #[test]
+    fn formula__unknown_variable__returns_err() -> Result<()> {
+        let formula: Formula = expect_ok!("1 USD + fee".parse());
+        let e = expect_err!(formula.evaluate(&HashMap::new()));
+        expect_eq!(
+            e,
+            Error::Expression {
+                position: 8,
+                message: "unknown variable 'fee'".to_string()
+            }
+        );
+        Ok(())
+    }
+}
@@ -2,8 +2,10 @@ use crate::currency;
 use crate::currency::Currency;
 use crate::error::Error;
 use crate::money::Money;
-use rust_decimal::{Decimal, RoundingStrategy};
+use crate::rounding::RoundingMode;
+use rust_decimal::Decimal;
 use std::cmp::{max, Ordering};
+use std::fmt::{Display, Formatter};
 use std::iter;
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
@@ -74,13 +76,35 @@ impl FractionalMoney {
         Ok(Self { currency, amount })
     }
 
+    /// Attempts to add a `Money` value to this one. Returns an error if the currencies do not
+    /// match.
+    pub fn try_add_money(&self, rhs: &Money) -> Result<Self, Error> {
+        self.try_add(&Self::from(*rhs))
+    }
+
+    /// Attempts to subtract a `Money` value from this one. Returns an error if the currencies do
+    /// not match.
+    pub fn try_subtract_money(&self, rhs: &Money) -> Result<Self, Error> {
+        self.try_subtract(&Self::from(*rhs))
+    }
+
     /// Round FractionalMoney to the maximum precision allowed by the currency and return a Money
     /// object. The rounding method is "Banker's rounding" a.k.a. "midpoint nearest even".
     pub fn round(&self) -> Money {
+        self.round_with(RoundingMode::MidpointNearestEven)
+    }
+
+    /// Similar to `round()` except that the rounding method is "midpoint away from zero"
+    pub fn round_up(&self) -> Money {
+        self.round_with(RoundingMode::MidpointAwayFromZero)
+    }
+
+    /// Similar to `round()` except that the rounding method is given by `mode`.
+    pub fn round_with(&self, mode: RoundingMode) -> Money {
         let precision = self.currency.max_precision();
         let mut rounded = self
             .amount
-            .round_dp_with_strategy(precision, RoundingStrategy::MidpointNearestEven);
+            .round_dp_with_strategy(precision, mode.strategy());
         rounded.rescale(precision);
 
         Money::new_unchecked(Self {
@@ -89,18 +113,39 @@ impl FractionalMoney {
         })
     }
 
-    /// Similar to `round()` except that the rounding method is "midpoint away from zero"
-    pub fn round_up(&self) -> Money {
+    /// Like `round()`, but also returns the remainder that was rounded away, such that
+    /// `rounded + remainder == self`.
+    pub fn round_with_remainder(&self) -> (Money, Self) {
+        let rounded = self.round();
+        let remainder = self.try_subtract_money(&rounded).unwrap();
+        (rounded, remainder)
+    }
+
+    /// Converts to `Money` without rounding. This only succeeds if the amount is already a valid
+    /// denomination of the currency, ignoring trailing zeros (e.g., 2.50000 USD but not
+    /// 2.505 USD). Otherwise, an `InvalidMoneyValue` error is returned, or an `Overflow` error if
+    /// the amount is too large to have the currency's number of decimal places.
+    pub fn try_into_money(&self) -> Result<Money, Error> {
         let precision = self.currency.max_precision();
-        let mut rounded = self
-            .amount
-            .round_dp_with_strategy(precision, RoundingStrategy::MidpointAwayFromZero);
-        rounded.rescale(precision);
+        if self.amount.normalize().scale() > precision {
+            let excess = self.amount - self.amount.trunc_with_scale(precision);
+            return Err(Error::InvalidMoneyValue(format!(
+                "expected at most {precision} significant decimal places for {:?}, but '{}' has \
+                excess digits {excess}",
+                self.currency, self.amount
+            )));
+        }
+        let mut amount = self.amount;
+        amount.rescale(precision);
+        // `rescale()` leaves the scale smaller if the amount would not fit.
+        if amount.scale() != precision {
+            return Err(Error::Overflow);
+        }
 
-        Money::new_unchecked(Self {
-            amount: rounded,
+        Ok(Money::new_unchecked(Self {
+            amount,
             currency: self.currency,
-        })
+        }))
     }
 
     /// Returns true if the `amount` is zero, regardless of currency.
@@ -135,6 +180,16 @@ impl Default for FractionalMoney {
     }
 }
 
+impl Display for FractionalMoney {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.currency == Currency::ZeroNone {
+            write!(f, "0")
+        } else {
+            write!(f, "{} {:?}", self.amount, self.currency)
+        }
+    }
+}
+
 impl Add for FractionalMoney {
     type Output = FractionalMoney;
 
@@ -163,6 +218,34 @@ impl SubAssign for FractionalMoney {
     }
 }
 
+impl Add<Money> for FractionalMoney {
+    type Output = FractionalMoney;
+
+    fn add(self, rhs: Money) -> Self::Output {
+        self.try_add_money(&rhs).unwrap()
+    }
+}
+
+impl AddAssign<Money> for FractionalMoney {
+    fn add_assign(&mut self, rhs: Money) {
+        *self = self.add(rhs);
+    }
+}
+
+impl Sub<Money> for FractionalMoney {
+    type Output = FractionalMoney;
+
+    fn sub(self, rhs: Money) -> Self::Output {
+        self.try_subtract_money(&rhs).unwrap()
+    }
+}
+
+impl SubAssign<Money> for FractionalMoney {
+    fn sub_assign(&mut self, rhs: Money) {
+        *self = self.sub(rhs);
+    }
+}
+
 impl Mul<Decimal> for FractionalMoney {
     type Output = FractionalMoney;
 
@@ -208,6 +291,15 @@ impl Neg for FractionalMoney {
     }
 }
 
+/// Exact conversion; see `FractionalMoney::try_into_money()`.
+impl TryFrom<FractionalMoney> for Money {
+    type Error = Error;
+
+    fn try_from(value: FractionalMoney) -> Result<Self, Self::Error> {
+        value.try_into_money()
+    }
+}
+
 /// If the iterator is empty, then the special `ZeroNone` currency will be the result.
 impl iter::Sum for FractionalMoney {
     fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
@@ -249,6 +341,14 @@ mod tests {
         FractionalMoney::default()
     }
 
+    #[test]
+    fn display() -> Result<()> {
+        expect_eq!(usd("13.375").to_string(), "13.375 USD");
+        expect_eq!(cad("-1").to_string(), "-1 CAD");
+        expect_eq!(zero().to_string(), "0");
+        Ok(())
+    }
+
     #[test]
     fn round() -> Result<()> {
         expect_eq!(usd("1").round(), usd!(1.00));
@@ -279,6 +379,98 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn round_with() -> Result<()> {
+        let value = usd("-1.015");
+        expect_eq!(
+            value.round_with(RoundingMode::MidpointNearestEven),
+            usd!(-1.02)
+        );
+        expect_eq!(
+            value.round_with(RoundingMode::MidpointAwayFromZero),
+            usd!(-1.02)
+        );
+        expect_eq!(
+            value.round_with(RoundingMode::MidpointTowardZero),
+            usd!(-1.01)
+        );
+        expect_eq!(value.round_with(RoundingMode::ToZero), usd!(-1.01));
+        expect_eq!(value.round_with(RoundingMode::AwayFromZero), usd!(-1.02));
+        expect_eq!(
+            value.round_with(RoundingMode::ToNegativeInfinity),
+            usd!(-1.02)
+        );
+        expect_eq!(
+            value.round_with(RoundingMode::ToPositiveInfinity),
+            usd!(-1.01)
+        );
+        expect_eq!(
+            usd("1.001").round_with(RoundingMode::ToPositiveInfinity),
+            usd!(1.01)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn round_with_remainder() -> Result<()> {
+        let (rounded, remainder) = usd("1.005").round_with_remainder();
+        expect_eq!(rounded, usd!(1.00));
+        expect_eq!(remainder, usd("0.005"));
+
+        let (rounded, remainder) = usd("1.0151").round_with_remainder();
+        expect_eq!(rounded, usd!(1.02));
+        expect_eq!(remainder, usd("-0.0049"));
+        expect_eq!(remainder + rounded, usd("1.0151"));
+
+        let (rounded, remainder) = usd("1.10").round_with_remainder();
+        expect_eq!(rounded, usd!(1.10));
+        expect!(remainder.is_zero());
+        Ok(())
+    }
+
+    #[test]
+    fn try_into_money() -> Result<()> {
+        expect_eq!(expect_ok!(usd("2.5").try_into_money()), usd!(2.50));
+        expect_eq!(expect_ok!(usd("2.50000").try_into_money()), usd!(2.50));
+        expect_eq!(expect_ok!(usd("-3").try_into_money()), usd!(-3));
+        expect_eq!(expect_ok!(zero().try_into_money()), Money::zero());
+        expect_eq!(
+            expect_ok!(usd("2.50000").try_into_money()).to_string(),
+            "2.50 USD"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn try_into_money__excess_digits__returns_err() -> Result<()> {
+        let e = expect_err!(usd("2.505").try_into_money());
+        expect_eq!(
+            e,
+            Error::InvalidMoneyValue(
+                "expected at most 2 significant decimal places for USD, but '2.505' has excess \
+                digits 0.005"
+                    .to_string()
+            )
+        );
+        expect_err!(usd("-0.0001").try_into_money());
+        Ok(())
+    }
+
+    #[test]
+    fn try_into_money__too_large__returns_err() -> Result<()> {
+        let max = FractionalMoney::new(Decimal::MAX, Currency::USD)?;
+        expect_eq!(expect_err!(max.try_into_money()), Error::Overflow);
+        Ok(())
+    }
+
+    #[test]
+    fn try_from() -> Result<()> {
+        let money: Money = expect_ok!(usd("13.3700").try_into());
+        expect_eq!(money, usd!(13.37));
+        expect_err!(Money::try_from(cad("13.375")));
+        Ok(())
+    }
+
     #[test]
     fn add__matching_currency() -> Result<()> {
         expect_eq!(usd("1") + usd("2.99"), usd("3.99"));
@@ -337,6 +529,46 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn add_money() -> Result<()> {
+        expect_eq!(usd("0.125") + usd!(1), usd("1.125"));
+        expect_eq!(zero() + usd!(1), usd("1.00"));
+
+        let mut accrual = usd("0.125");
+        accrual += usd!(2);
+        expect_eq!(accrual, usd("2.125"));
+        Ok(())
+    }
+
+    #[test]
+    fn subtract_money() -> Result<()> {
+        expect_eq!(usd("0.125") - usd!(1), usd("-0.875"));
+
+        let mut accrual = usd("2.125");
+        accrual -= usd!(2);
+        expect_eq!(accrual, usd("0.125"));
+        Ok(())
+    }
+
+    #[test]
+    fn try_add_money__mismatched_currency__returns_err() -> Result<()> {
+        expect_eq!(
+            expect_err!(cad("1").try_add_money(&usd!(1))),
+            Error::MismatchedCurrency
+        );
+        expect_eq!(
+            expect_err!(cad("1").try_subtract_money(&usd!(1))),
+            Error::MismatchedCurrency
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic]
+    fn add_money__mismatched_currencies__panics() {
+        let _ = cad("1") + usd!(1);
+    }
+
     #[test]
     fn multiply_by_0() -> Result<()> {
         expect_eq!(usd("1") * dec!(0), usd("0"));
@@ -0,0 +1,642 @@
+use crate::error::Error;
+use crate::fractional_money::FractionalMoney;
+use crate::money::Money;
+use crate::tvm;
+use chrono::{Datelike, NaiveDate};
+use rust_decimal::{Decimal, MathematicalOps};
+
+/// A convention for measuring the length of a period in years, for the purposes of accruing
+/// interest.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum DayCount {
+    /// Actual days elapsed divided by 365 (ACT/365F).
+    #[default]
+    Act365Fixed,
+    /// Actual days elapsed divided by 360 (ACT/360).
+    Act360,
+    /// Every month has 30 days and every year has 360, with the end-of-month adjustments of the
+    /// US (NASD) convention (30/360 US).
+    Thirty360Us,
+    /// Days elapsed in leap years divided by 366, plus days elapsed in other years divided by 365
+    /// (ACT/ACT ISDA).
+    ActActIsda,
+}
+
+impl DayCount {
+    /// The length in years of the period from `start` (inclusive) to `end` (exclusive). Returns an
+    /// error if `end` is before `start`.
+    pub fn year_fraction(&self, start: NaiveDate, end: NaiveDate) -> Result<Decimal, Error> {
+        let (days, basis) = self.fraction(start, end)?;
+        Ok(Decimal::from(days) / Decimal::from(basis))
+    }
+
+    /// The year fraction as an exact ratio of days to days-per-year.
+    fn fraction(&self, start: NaiveDate, end: NaiveDate) -> Result<(i64, i64), Error> {
+        if end < start {
+            return Err(Error::InvalidArgument(format!(
+                "end date {end} is before start date {start}"
+            )));
+        }
+        let actual_days = (end - start).num_days();
+        Ok(match self {
+            Self::Act365Fixed => (actual_days, 365),
+            Self::Act360 => (actual_days, 360),
+            Self::Thirty360Us => (thirty_360_us_days(start, end), 360),
+            Self::ActActIsda => {
+                let (mut normal_days, mut leap_days) = (0, 0);
+                for year in start.year()..=end.year() {
+                    let from = start.max(first_day_of_year(year));
+                    let to = end.min(first_day_of_year(year + 1));
+                    let days = (to - from).num_days();
+                    if is_leap_year(year) {
+                        leap_days += days;
+                    } else {
+                        normal_days += days;
+                    }
+                }
+                (normal_days * 366 + leap_days * 365, 365 * 366)
+            }
+        })
+    }
+}
+
+fn first_day_of_year(year: i32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, 1, 1).unwrap()
+}
+
+fn is_leap_year(year: i32) -> bool {
+    NaiveDate::from_ymd_opt(year, 2, 29).is_some()
+}
+
+fn is_last_day_of_february(date: NaiveDate) -> bool {
+    date.month() == 2 && date.succ_opt().is_some_and(|next| next.month() == 3)
+}
+
+fn thirty_360_us_days(start: NaiveDate, end: NaiveDate) -> i64 {
+    let (mut d1, mut d2) = (start.day() as i64, end.day() as i64);
+    if is_last_day_of_february(start) && is_last_day_of_february(end) {
+        d2 = 30;
+    }
+    if is_last_day_of_february(start) {
+        d1 = 30;
+    }
+    if d2 == 31 && d1 >= 30 {
+        d2 = 30;
+    }
+    if d1 == 31 {
+        d1 = 30;
+    }
+    let years = (end.year() - start.year()) as i64;
+    let months = end.month() as i64 - start.month() as i64;
+    360 * years + 30 * months + (d2 - d1)
+}
+
+/// How often interest is compounded.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Compounding {
+    /// Interest is never compounded.
+    #[default]
+    Simple,
+    /// Compounded 365 times per year.
+    Daily,
+    Weekly,
+    BiWeekly,
+    Monthly,
+    Quarterly,
+    SemiAnnual,
+    Annual,
+    Continuous,
+}
+
+impl Compounding {
+    /// The number of compounding periods per year, or `None` for simple and continuous
+    /// compounding.
+    pub fn periods_per_year(&self) -> Option<u32> {
+        match self {
+            Self::Simple | Self::Continuous => None,
+            Self::Daily => Some(365),
+            Self::Weekly => Some(52),
+            Self::BiWeekly => Some(26),
+            Self::Monthly => Some(12),
+            Self::Quarterly => Some(4),
+            Self::SemiAnnual => Some(2),
+            Self::Annual => Some(1),
+        }
+    }
+
+    /// The rate per period, for `periods_per_year` periods per year, which is equivalent to the
+    /// nominal annual `rate` compounded this often, e.g., the monthly rate equivalent to 6%
+    /// compounded semi-annually. Simple interest is treated as compounding once per period.
+    /// Returns `None` if `periods_per_year` is zero or the calculation overflows.
+    pub fn equivalent_periodic_rate(
+        &self,
+        rate: Decimal,
+        periods_per_year: u32,
+    ) -> Option<Decimal> {
+        if periods_per_year == 0 {
+            return None;
+        }
+        let per_period = rate.checked_div(Decimal::from(periods_per_year));
+        let factor = match self.periods_per_year() {
+            None if *self == Self::Continuous => per_period?.checked_exp()?,
+            None => return per_period,
+            Some(m) => {
+                // (1 + r/m)^(m/n), computed as an integer power and an integer root.
+                let base = rate
+                    .checked_div(Decimal::from(m))?
+                    .checked_add(Decimal::ONE)?;
+                let divisor = gcd(m, periods_per_year);
+                nth_root(
+                    base.checked_powi((m / divisor).into())?,
+                    periods_per_year / divisor,
+                )?
+            }
+        };
+        factor.checked_sub(Decimal::ONE)
+    }
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// The `n`th root of a positive value, by Newton's method.
+fn nth_root(value: Decimal, n: u32) -> Option<Decimal> {
+    if !value.is_sign_positive() || value.is_zero() {
+        return None;
+    }
+    if n == 1 {
+        return Some(value);
+    }
+    let n_dec = Decimal::from(n);
+    let mut x = Decimal::ONE + (value - Decimal::ONE) / n_dec;
+    for _ in 0..100 {
+        let next = ((n_dec - Decimal::ONE) * x
+            + value.checked_div(x.checked_powi((n - 1).into())?)?)
+            / n_dec;
+        if (next - x).abs() <= Decimal::new(1, 27) {
+            return Some(next);
+        }
+        x = next;
+    }
+    Some(x)
+}
+
+/// A nominal annual interest rate together with how often it is compounded, e.g., 6% compounded
+/// semi-annually. Rates are fractions, e.g., `dec!(0.06)` for 6%.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InterestRate {
+    pub nominal: Decimal,
+    pub compounding: Compounding,
+}
+
+impl InterestRate {
+    pub fn new(nominal: Decimal, compounding: Compounding) -> Self {
+        Self {
+            nominal,
+            compounding,
+        }
+    }
+
+    /// The nominal rate, compounded as given, whose effective annual rate is `effective`. Returns
+    /// an error if `effective` is not greater than -1 or the calculation overflows.
+    pub fn from_effective_annual(
+        effective: Decimal,
+        compounding: Compounding,
+    ) -> Result<Self, Error> {
+        let growth = Decimal::ONE + effective;
+        if !growth.is_sign_positive() || growth.is_zero() {
+            return Err(Error::InvalidArgument(format!(
+                "expected an effective rate greater than -1, but got {effective}"
+            )));
+        }
+        let nominal = match compounding.periods_per_year() {
+            None if compounding == Compounding::Continuous => growth.checked_ln(),
+            None => Some(effective),
+            Some(m) => nth_root(growth, m)
+                .and_then(|root| (root - Decimal::ONE).checked_mul(Decimal::from(m))),
+        };
+        Ok(Self::new(nominal.ok_or(Error::Overflow)?, compounding))
+    }
+
+    /// The annual percentage rate (APR) of a loan of `principal` repaid by `payments` made every
+    /// `period` (e.g., `Compounding::Monthly`), where `fees` are deducted from the amount
+    /// financed. This is the internal rate of return per period times the number of periods per
+    /// year, as in the actuarial method of the US Truth in Lending Act. Returns an error if the
+    /// currencies do not match, `period` is simple or continuous, or no rate is found.
+    pub fn apr(
+        principal: Money,
+        fees: Money,
+        payments: &[Money],
+        period: Compounding,
+    ) -> Result<Self, Error> {
+        let Some(periods_per_year) = period.periods_per_year() else {
+            return Err(Error::InvalidArgument(format!(
+                "expected a payment period, but got {period:?}"
+            )));
+        };
+        let mut flows = vec![principal.try_subtract(&fees)?];
+        flows.extend(payments.iter().map(|p| -*p));
+        let rate = tvm::irr(&flows)?;
+        let nominal = rate
+            .checked_mul(Decimal::from(periods_per_year))
+            .ok_or(Error::Overflow)?;
+        Ok(Self::new(nominal, period))
+    }
+
+    /// The effective annual rate, i.e., the annual percentage yield (APY). Returns an error if the
+    /// calculation overflows.
+    pub fn effective_annual(&self) -> Result<Decimal, Error> {
+        self.periodic(1)
+    }
+
+    /// The equivalent rate per period, for `periods_per_year` periods per year, e.g., the monthly
+    /// rate of a mortgage whose rate is compounded semi-annually. Returns an error if
+    /// `periods_per_year` is zero or the calculation overflows.
+    pub fn periodic(&self, periods_per_year: u32) -> Result<Decimal, Error> {
+        if periods_per_year == 0 {
+            return Err(Error::InvalidArgument(
+                "expected at least one period per year".to_string(),
+            ));
+        }
+        self.compounding
+            .equivalent_periodic_rate(self.nominal, periods_per_year)
+            .ok_or(Error::Overflow)
+    }
+
+    /// The equivalent nominal rate with different compounding, i.e., with the same effective
+    /// annual rate. Returns an error if the calculation overflows.
+    pub fn convert(&self, compounding: Compounding) -> Result<Self, Error> {
+        Self::from_effective_annual(self.effective_annual()?, compounding)
+    }
+}
+
+/// A method of accruing interest: a nominal annual rate, how often it is compounded, and the
+/// day-count convention used to measure time. Accruals are returned as `FractionalMoney` so that
+/// they are only rounded when posted, e.g., with `round()`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Interest {
+    /// The nominal annual rate, e.g., `dec!(0.05)` for 5%.
+    pub rate: Decimal,
+    pub compounding: Compounding,
+    pub day_count: DayCount,
+}
+
+impl Interest {
+    pub fn new(rate: Decimal, compounding: Compounding, day_count: DayCount) -> Self {
+        Self {
+            rate,
+            compounding,
+            day_count,
+        }
+    }
+
+    /// Simple (non-compounding) interest at the given nominal annual rate.
+    pub fn simple(rate: Decimal, day_count: DayCount) -> Self {
+        Self::new(rate, Compounding::Simple, day_count)
+    }
+
+    /// The factor by which a balance grows from `start` to `end`, e.g., 1.05 if it earns 5%.
+    /// Returns an error if `end` is before `start` or if the calculation overflows.
+    pub fn growth_factor(&self, start: NaiveDate, end: NaiveDate) -> Result<Decimal, Error> {
+        let (days, basis) = self.day_count.fraction(start, end)?;
+        let (days, basis) = (Decimal::from(days), Decimal::from(basis));
+        let factor = match self.compounding.periods_per_year() {
+            None => {
+                let exponent = self
+                    .rate
+                    .checked_mul(days)
+                    .and_then(|d| d.checked_div(basis));
+                match self.compounding {
+                    Compounding::Continuous => exponent.and_then(|e| e.checked_exp()),
+                    _ => exponent.and_then(|e| e.checked_add(Decimal::ONE)),
+                }
+            }
+            Some(n) => {
+                let n = Decimal::from(n);
+                let base = self
+                    .rate
+                    .checked_div(n)
+                    .and_then(|r| r.checked_add(Decimal::ONE));
+                let periods = n * days;
+                // Use exact integer powers where possible, since fractional powers are
+                // approximated.
+                base.and_then(|b| {
+                    if (periods % basis).is_zero() {
+                        let periods: i64 = (periods / basis).try_into().ok()?;
+                        b.checked_powi(periods)
+                    } else {
+                        b.checked_powd(periods.checked_div(basis)?)
+                    }
+                })
+            }
+        };
+        factor.ok_or(Error::Overflow)
+    }
+
+    /// The interest accrued on `principal` from `start` to `end`. Returns an error if `end` is
+    /// before `start` or if the calculation overflows.
+    pub fn accrue(
+        &self,
+        principal: &FractionalMoney,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<FractionalMoney, Error> {
+        let rate = self.growth_factor(start, end)? - Decimal::ONE;
+        let amount = principal
+            .amount()
+            .checked_mul(rate)
+            .ok_or(Error::Overflow)?;
+        FractionalMoney::new(amount, principal.currency())
+    }
+
+    /// The balance at `end` of `principal` invested at `start`, i.e., the principal plus the
+    /// accrued interest. Returns an error if `end` is before `start` or if the calculation
+    /// overflows.
+    pub fn accumulate(
+        &self,
+        principal: &FractionalMoney,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<FractionalMoney, Error> {
+        principal.try_add(&self.accrue(principal, start, end)?)
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::currency::Currency;
+    use crate::usd;
+    use anyhow::Result;
+    use expecting::*;
+    use rust_decimal_macros::dec;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn usd(d: &str) -> FractionalMoney {
+        FractionalMoney::new(Decimal::from_str_exact(d).unwrap(), Currency::USD).unwrap()
+    }
+
+    #[test]
+    fn year_fraction__actual() -> Result<()> {
+        let (start, end) = (date(2024, 1, 1), date(2024, 3, 14));
+        expect_eq!(
+            DayCount::Act365Fixed.year_fraction(start, end)?,
+            dec!(73) / dec!(365)
+        );
+        expect_eq!(
+            DayCount::Act360.year_fraction(start, end)?,
+            dec!(73) / dec!(360)
+        );
+        expect_eq!(DayCount::Act360.year_fraction(start, start)?, dec!(0));
+        Ok(())
+    }
+
+    #[test]
+    fn year_fraction__thirty_360_us() -> Result<()> {
+        let days = |start, end| DayCount::Thirty360Us.fraction(start, end).unwrap().0;
+        expect_eq!(days(date(2024, 1, 15), date(2024, 2, 15)), 30);
+        expect_eq!(days(date(2024, 1, 31), date(2024, 3, 31)), 60);
+        expect_eq!(days(date(2024, 1, 30), date(2024, 3, 31)), 60);
+        expect_eq!(days(date(2024, 1, 15), date(2024, 3, 31)), 76);
+        expect_eq!(days(date(2024, 2, 29), date(2024, 3, 31)), 30);
+        expect_eq!(days(date(2023, 2, 28), date(2024, 2, 29)), 360);
+        expect_eq!(days(date(2023, 2, 28), date(2024, 2, 28)), 358);
+        expect_eq!(
+            DayCount::Thirty360Us.year_fraction(date(2023, 6, 30), date(2024, 6, 30))?,
+            dec!(1)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn year_fraction__act_act_isda() -> Result<()> {
+        expect_eq!(
+            DayCount::ActActIsda.year_fraction(date(2023, 1, 1), date(2025, 1, 1))?,
+            dec!(2)
+        );
+        expect_eq!(
+            DayCount::ActActIsda.fraction(date(2023, 7, 1), date(2024, 7, 1))?,
+            (184 * 366 + 182 * 365, 365 * 366)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn year_fraction__end_before_start__returns_err() -> Result<()> {
+        let e = expect_err!(DayCount::Act360.year_fraction(date(2024, 2, 1), date(2024, 1, 1)));
+        expect_eq!(
+            e,
+            Error::InvalidArgument(
+                "end date 2024-01-01 is before start date 2024-02-01".to_string()
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn accrue__simple() -> Result<()> {
+        let principal = usd("1000");
+        let interest = Interest::simple(dec!(0.05), DayCount::Act365Fixed);
+        expect_eq!(
+            interest.accrue(&principal, date(2024, 1, 1), date(2024, 3, 14))?,
+            usd("10")
+        );
+
+        let interest = Interest::simple(dec!(0.04), DayCount::Act360);
+        let accrued = interest.accrue(&principal, date(2024, 1, 1), date(2024, 1, 2))?;
+        expect_eq!(accrued.round(), usd!(0.11));
+        expect_eq!(
+            interest.accrue(&principal, date(2024, 1, 1), date(2024, 3, 31))?,
+            usd("10")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn accrue__compound() -> Result<()> {
+        let principal = usd("1000");
+
+        let annual = Interest::new(dec!(0.1), Compounding::Annual, DayCount::ActActIsda);
+        expect_eq!(
+            annual.accrue(&principal, date(2023, 1, 1), date(2025, 1, 1))?,
+            usd("210")
+        );
+        expect_eq!(
+            annual.accumulate(&principal, date(2023, 1, 1), date(2025, 1, 1))?,
+            usd("1210")
+        );
+
+        let monthly = Interest::new(dec!(0.06), Compounding::Monthly, DayCount::Thirty360Us);
+        let accrued = monthly.accrue(&principal, date(2024, 1, 15), date(2025, 1, 15))?;
+        expect_eq!(accrued.amount().round_dp(20), dec!(61.67781186449956878971));
+
+        let daily = Interest::new(dec!(0.05), Compounding::Daily, DayCount::Act365Fixed);
+        let accrued = daily.accrue(&principal, date(2023, 1, 1), date(2024, 1, 1))?;
+        expect_eq!(accrued.amount().round_dp(10), dec!(51.2674964675));
+
+        let semi_annual = Interest::new(dec!(0.06), Compounding::SemiAnnual, DayCount::Act365Fixed);
+        let accrued = semi_annual.accrue(&principal, date(2023, 1, 1), date(2024, 1, 1))?;
+        expect_eq!(accrued, usd("60.9"));
+        Ok(())
+    }
+
+    #[test]
+    fn accrue__fractional_periods() -> Result<()> {
+        let interest = Interest::new(dec!(0.1), Compounding::Annual, DayCount::ActActIsda);
+        let accrued = interest.accrue(&usd("1000"), date(2023, 1, 1), date(2024, 7, 1))?;
+        expect_eq!(accrued.amount().round_dp(8), dec!(153.38933938));
+        expect_eq!(accrued.round(), usd!(153.39));
+        Ok(())
+    }
+
+    #[test]
+    fn accrue__continuous() -> Result<()> {
+        let interest = Interest::new(dec!(0.05), Compounding::Continuous, DayCount::ActActIsda);
+        let accrued = interest.accrue(&usd("1000"), date(2023, 1, 1), date(2024, 1, 1))?;
+        expect_eq!(accrued.amount().round_dp(8), dec!(51.27109638));
+        expect_eq!(accrued.round(), usd!(51.27));
+        Ok(())
+    }
+
+    #[test]
+    fn accrue__end_before_start__returns_err() -> Result<()> {
+        let interest = Interest::simple(dec!(0.05), DayCount::Act365Fixed);
+        expect_err!(interest.accrue(&usd("1000"), date(2024, 2, 1), date(2024, 1, 1)));
+        Ok(())
+    }
+
+    #[test]
+    fn equivalent_periodic_rate() -> Result<()> {
+        let semi_annual = Compounding::SemiAnnual;
+        // 1.03^(1/6) - 1
+        expect_eq!(
+            expect_some!(semi_annual.equivalent_periodic_rate(dec!(0.06), 12)).round_dp(20),
+            dec!(0.00493862203119697841)
+        );
+        expect_eq!(
+            expect_some!(semi_annual.equivalent_periodic_rate(dec!(0.06), 2)),
+            dec!(0.03)
+        );
+        // 1.03^2 - 1
+        expect_eq!(
+            expect_some!(semi_annual.equivalent_periodic_rate(dec!(0.06), 1)),
+            dec!(0.0609)
+        );
+        expect_eq!(
+            expect_some!(Compounding::Monthly.equivalent_periodic_rate(dec!(0.06), 12)),
+            dec!(0.005)
+        );
+        expect_eq!(
+            expect_some!(Compounding::Simple.equivalent_periodic_rate(dec!(0.06), 12)),
+            dec!(0.005)
+        );
+        expect_eq!(
+            expect_some!(Compounding::Continuous.equivalent_periodic_rate(dec!(0.05), 1))
+                .round_dp(12),
+            dec!(0.051271096376)
+        );
+        expect_none!(semi_annual.equivalent_periodic_rate(dec!(0.06), 0));
+        Ok(())
+    }
+
+    #[test]
+    fn interest_rate__effective_annual() -> Result<()> {
+        let rate = InterestRate::new(dec!(0.06), Compounding::SemiAnnual);
+        expect_eq!(rate.effective_annual()?, dec!(0.0609));
+        let rate = InterestRate::new(dec!(0.12), Compounding::Monthly);
+        expect_eq!(rate.effective_annual()?.round_dp(12), dec!(0.126825030132));
+        let rate = InterestRate::new(dec!(0.05), Compounding::Continuous);
+        expect_eq!(rate.effective_annual()?.round_dp(8), dec!(0.05127110));
+        let rate = InterestRate::new(dec!(0.05), Compounding::Simple);
+        expect_eq!(rate.effective_annual()?, dec!(0.05));
+        Ok(())
+    }
+
+    #[test]
+    fn interest_rate__periodic() -> Result<()> {
+        let rate = InterestRate::new(dec!(0.06), Compounding::SemiAnnual);
+        expect_eq!(rate.periodic(2)?, dec!(0.03));
+        expect_eq!(rate.periodic(12)?.round_dp(10), dec!(0.0049386220));
+        expect_err!(rate.periodic(0));
+        Ok(())
+    }
+
+    #[test]
+    fn interest_rate__from_effective_annual() -> Result<()> {
+        let rate = InterestRate::from_effective_annual(dec!(0.0609), Compounding::SemiAnnual)?;
+        expect_eq!(rate.nominal.round_dp(20), dec!(0.06));
+        expect_eq!(rate.compounding, Compounding::SemiAnnual);
+
+        let rate = InterestRate::from_effective_annual(dec!(0.0609), Compounding::Continuous)?;
+        expect_eq!(rate.nominal.round_dp(10), dec!(0.0591176045));
+        expect_err!(InterestRate::from_effective_annual(
+            dec!(-1),
+            Compounding::Annual
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn interest_rate__convert() -> Result<()> {
+        let semi_annual = InterestRate::new(dec!(0.06), Compounding::SemiAnnual);
+        let monthly = semi_annual.convert(Compounding::Monthly)?;
+        expect_eq!(monthly.nominal.round_dp(12), dec!(0.059263464374));
+        expect_eq!(monthly.compounding, Compounding::Monthly);
+        let back = monthly.convert(Compounding::SemiAnnual)?;
+        expect_eq!(back.nominal.round_dp(20), dec!(0.06));
+        Ok(())
+    }
+
+    #[test]
+    fn interest_rate__apr_includes_fees() -> Result<()> {
+        let payments = vec![usd!(332.14); 36];
+        let without_fees =
+            InterestRate::apr(usd!(10000), Money::zero(), &payments, Compounding::Monthly)?;
+        expect_eq!(without_fees.nominal.round_dp(4), dec!(0.12));
+
+        let apr = InterestRate::apr(usd!(10000), usd!(200), &payments, Compounding::Monthly)?;
+        expect_eq!(apr.nominal.round_dp(6), dec!(0.134101));
+        expect_eq!(apr.compounding, Compounding::Monthly);
+        expect_eq!(apr.effective_annual()?.round_dp(4), dec!(0.1427));
+        Ok(())
+    }
+
+    #[test]
+    fn interest_rate__apr__invalid_arguments__returns_err() -> Result<()> {
+        let payments = [usd!(100), usd!(100)];
+        expect_err!(InterestRate::apr(
+            usd!(190),
+            Money::zero(),
+            &payments,
+            Compounding::Continuous
+        ));
+        expect_eq!(
+            expect_err!(InterestRate::apr(
+                usd!(190),
+                crate::cad!(1),
+                &payments,
+                Compounding::Monthly
+            )),
+            Error::MismatchedCurrency
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn periods_per_year() -> Result<()> {
+        expect_eq!(Compounding::Daily.periods_per_year(), Some(365));
+        expect_eq!(Compounding::Monthly.periods_per_year(), Some(12));
+        expect_eq!(Compounding::Continuous.periods_per_year(), None);
+        expect_eq!(Compounding::Simple.periods_per_year(), None);
+        Ok(())
+    }
+}
@@ -0,0 +1,620 @@
+use crate::context::MoneyContext;
+use crate::currency::Currency;
+use crate::error::Error;
+use crate::fractional_money::FractionalMoney;
+use crate::money::Money;
+use crate::tax::{PriceMode, SalesTax, TaxAmount};
+use rust_decimal::Decimal;
+
+/// A line of an `Invoice`: a quantity at a unit price, which may have more decimal places than the
+/// currency, e.g., 1.5 kg at 9.999 USD/kg.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvoiceLine {
+    pub description: String,
+    pub quantity: Decimal,
+    pub unit_price: FractionalMoney,
+    /// The fraction taken off the line's amount, e.g., `dec!(0.1)` for 10% off.
+    pub discount: Decimal,
+}
+
+impl InvoiceLine {
+    pub fn new(
+        description: impl Into<String>,
+        quantity: Decimal,
+        unit_price: FractionalMoney,
+    ) -> Self {
+        Self {
+            description: description.into(),
+            quantity,
+            unit_price,
+            discount: Decimal::ZERO,
+        }
+    }
+
+    pub fn with_discount(mut self, discount: Decimal) -> Self {
+        self.discount = discount;
+        self
+    }
+}
+
+/// A discount on a whole invoice.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvoiceDiscount {
+    pub description: String,
+    pub amount: Money,
+}
+
+/// An invoice: lines, discounts, shipping and sales tax in a single currency.
+///
+/// Amounts are calculated with the invoice's `MoneyContext` and rounded with its rounding method:
+/// each line's amount and discount is rounded, invoice discounts are prorated over the lines with
+/// `Money::allocate()` (so that taxes apply to the discounted amounts), and taxes are calculated on
+/// the lines by the `SalesTax`. If the context uses cash rounding, then the total is rounded to a
+/// cash denomination and the difference is shown as a rounding adjustment.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Invoice {
+    currency: Currency,
+    lines: Vec<InvoiceLine>,
+    discounts: Vec<InvoiceDiscount>,
+    shipping: Option<Money>,
+    shipping_taxable: bool,
+    tax: Option<SalesTax>,
+    context: MoneyContext,
+}
+
+impl Invoice {
+    /// Creates an empty invoice with no tax, using the default `MoneyContext`.
+    pub fn new(currency: Currency) -> Self {
+        Self {
+            currency,
+            lines: vec![],
+            discounts: vec![],
+            shipping: None,
+            shipping_taxable: false,
+            tax: None,
+            context: MoneyContext::default(),
+        }
+    }
+
+    pub fn with_line(mut self, line: InvoiceLine) -> Self {
+        self.lines.push(line);
+        self
+    }
+
+    pub fn with_discount(mut self, description: impl Into<String>, amount: Money) -> Self {
+        self.discounts.push(InvoiceDiscount {
+            description: description.into(),
+            amount,
+        });
+        self
+    }
+
+    /// Sets the shipping charge, which is taxed like a line if `taxable` is true.
+    pub fn with_shipping(mut self, amount: Money, taxable: bool) -> Self {
+        self.shipping = Some(amount);
+        self.shipping_taxable = taxable;
+        self
+    }
+
+    pub fn with_tax(mut self, tax: SalesTax) -> Self {
+        self.tax = Some(tax);
+        self
+    }
+
+    pub fn with_context(mut self, context: MoneyContext) -> Self {
+        self.context = context;
+        self
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    pub fn lines(&self) -> &[InvoiceLine] {
+        &self.lines
+    }
+
+    /// Calculates the invoice's amounts. Returns an error if a currency does not match the
+    /// invoice's, a quantity or discount is negative, a line discount is more than 100%, or the
+    /// discounts exceed the subtotal.
+    pub fn summary(&self) -> Result<InvoiceSummary, Error> {
+        let zero = Money::new(Decimal::ZERO, self.currency)?;
+        let rounding = self.context.rounding;
+
+        let mut lines = vec![];
+        for line in &self.lines {
+            self.check_currency(line.unit_price.currency())?;
+            if line.quantity < Decimal::ZERO {
+                return Err(self.invalid(line, format!("negative quantity {}", line.quantity)));
+            }
+            if line.discount < Decimal::ZERO || line.discount > Decimal::ONE {
+                return Err(self.invalid(line, format!("invalid discount {}", line.discount)));
+            }
+            let amount = self
+                .context
+                .multiply(&line.unit_price, line.quantity)?
+                .round_with(rounding);
+            let discount = self
+                .context
+                .multiply(&amount.into(), line.discount)?
+                .round_with(rounding);
+            lines.push(InvoiceLineTotal {
+                description: line.description.clone(),
+                quantity: line.quantity,
+                unit_price: line.unit_price,
+                amount,
+                discount,
+                net: amount.try_subtract(&discount)?,
+            });
+        }
+        let subtotal = lines
+            .iter()
+            .try_fold(zero, |sum, line| sum.try_add(&line.net))?;
+
+        let mut discount = zero;
+        for invoice_discount in &self.discounts {
+            self.check_currency(invoice_discount.amount.currency())?;
+            if invoice_discount.amount.is_negative() {
+                return Err(Error::InvalidArgument(format!(
+                    "discount {}: negative amount {}",
+                    invoice_discount.description, invoice_discount.amount
+                )));
+            }
+            discount = discount.try_add(&invoice_discount.amount)?;
+        }
+        if discount > subtotal {
+            return Err(Error::InvalidArgument(format!(
+                "the discounts of {discount} exceed the subtotal of {subtotal}"
+            )));
+        }
+
+        // The discounted lines, and shipping if it is taxable, are taxed.
+        let mut taxable: Vec<Money> = lines.iter().map(|line| line.net).collect();
+        if !discount.is_zero() {
+            let ratios: Vec<Decimal> = taxable.iter().map(|net| net.amount()).collect();
+            for (net, share) in taxable.iter_mut().zip(discount.allocate(&ratios)?) {
+                *net = net.try_subtract(&share)?;
+            }
+        }
+        let shipping = self.shipping.unwrap_or(zero);
+        self.check_currency(shipping.currency())?;
+        if shipping.is_negative() {
+            return Err(Error::InvalidArgument(format!(
+                "negative shipping amount {shipping}"
+            )));
+        }
+        let mut untaxed = zero;
+        if self.shipping_taxable {
+            taxable.push(shipping);
+        } else {
+            untaxed = shipping;
+        }
+
+        let (taxes, taxed) = match &self.tax {
+            Some(tax) => {
+                let result = tax.apply_invoice(&taxable)?.total;
+                (result.taxes, result.gross)
+            }
+            None => {
+                let sum = taxable.iter().try_fold(zero, |sum, net| sum.try_add(net))?;
+                (vec![], sum)
+            }
+        };
+        let tax = taxes
+            .iter()
+            .try_fold(zero, |sum, tax| sum.try_add(&tax.amount))?;
+
+        let unrounded = zero.try_add(&taxed)?.try_add(&untaxed)?;
+        let total = self.context.round(&unrounded.into())?;
+        Ok(InvoiceSummary {
+            currency: self.currency,
+            lines,
+            subtotal,
+            discounts: self.discounts.clone(),
+            discount,
+            shipping,
+            taxes,
+            tax,
+            tax_inclusive: self
+                .tax
+                .as_ref()
+                .is_some_and(|tax| tax.mode() == PriceMode::TaxInclusive),
+            rounding_adjustment: total.try_subtract(&unrounded)?,
+            total,
+        })
+    }
+
+    fn check_currency(&self, currency: Currency) -> Result<(), Error> {
+        if currency != self.currency {
+            return Err(Error::MismatchedCurrency);
+        }
+        Ok(())
+    }
+
+    fn invalid(&self, line: &InvoiceLine, details: String) -> Error {
+        Error::InvalidArgument(format!("line {}: {details}", line.description))
+    }
+}
+
+/// A line of an `InvoiceSummary`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvoiceLineTotal {
+    pub description: String,
+    pub quantity: Decimal,
+    pub unit_price: FractionalMoney,
+    /// The quantity times the unit price, rounded.
+    pub amount: Money,
+    pub discount: Money,
+    /// `amount - discount`.
+    pub net: Money,
+}
+
+/// The calculated amounts of an `Invoice`, where
+/// `subtotal - discount + shipping + tax + rounding_adjustment == total`, or without `tax` if the
+/// prices include it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvoiceSummary {
+    pub currency: Currency,
+    pub lines: Vec<InvoiceLineTotal>,
+    /// The sum of the lines' net amounts.
+    pub subtotal: Money,
+    pub discounts: Vec<InvoiceDiscount>,
+    /// The sum of `discounts`.
+    pub discount: Money,
+    pub shipping: Money,
+    /// The amount of each tax, in the order of the rates.
+    pub taxes: Vec<TaxAmount>,
+    /// The sum of `taxes`.
+    pub tax: Money,
+    /// If true, the prices include `tax`, so it is not added to the total.
+    pub tax_inclusive: bool,
+    /// The difference between the total and the total rounded to a cash denomination.
+    pub rounding_adjustment: Money,
+    pub total: Money,
+}
+
+impl InvoiceSummary {
+    /// Renders the invoice as a plain-text table with aligned columns.
+    pub fn to_text(&self) -> String {
+        let rows = self.rows();
+        let mut widths = HEADERS.map(str::len);
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        let totals = self.totals();
+        let label_width = widths[..4].iter().sum::<usize>() + 3 * COLUMN_GAP.len();
+        for (_, amount) in &totals {
+            widths[4] = widths[4].max(amount.chars().count());
+        }
+
+        let format_row = |row: &[String; 5]| {
+            let mut cells = vec![format!("{:<1$}", row[0], widths[0])];
+            for (cell, width) in row.iter().zip(widths).skip(1) {
+                cells.push(format!("{cell:>width$}"));
+            }
+            cells.join(COLUMN_GAP).trim_end().to_string()
+        };
+        let mut out = vec![format_row(&HEADERS.map(String::from))];
+        out.push(format_row(&widths.map(|width| "-".repeat(width))));
+        out.extend(rows.iter().map(format_row));
+        out.push(String::new());
+        for (label, amount) in totals {
+            out.push(format!(
+                "{label:>label_width$}{COLUMN_GAP}{amount:>0$}",
+                widths[4]
+            ));
+        }
+        out.join("\n") + "\n"
+    }
+
+    /// Renders the invoice as a Markdown table, with the totals in the last rows.
+    pub fn to_markdown(&self) -> String {
+        let escape = |s: &str| s.replace('|', "\\|");
+        let mut out = vec![
+            format!("| {} |", HEADERS.join(" | ")),
+            "| :--- | ---: | ---: | ---: | ---: |".to_string(),
+        ];
+        for row in self.rows() {
+            let [description, rest @ ..] = row;
+            out.push(format!(
+                "| {} | {} |",
+                escape(&description),
+                rest.join(" | ")
+            ));
+        }
+        for (label, amount) in self.totals() {
+            out.push(format!("| **{}** | | | | **{amount}** |", escape(&label)));
+        }
+        out.join("\n") + "\n"
+    }
+
+    /// Renders the invoice as an HTML table, with the totals in its footer.
+    pub fn to_html(&self) -> String {
+        let mut out = vec![
+            "<table>".to_string(),
+            "  <thead>".to_string(),
+            "    <tr>".to_string(),
+        ];
+        for header in HEADERS {
+            out.push(format!("      <th>{header}</th>"));
+        }
+        out.extend(["    </tr>", "  </thead>", "  <tbody>"].map(String::from));
+        for row in self.rows() {
+            out.push("    <tr>".to_string());
+            for cell in row {
+                out.push(format!("      <td>{}</td>", escape_html(&cell)));
+            }
+            out.push("    </tr>".to_string());
+        }
+        out.extend(["  </tbody>", "  <tfoot>"].map(String::from));
+        for (label, amount) in self.totals() {
+            out.push(format!(
+                "    <tr><th colspan=\"4\">{}</th><td>{amount}</td></tr>",
+                escape_html(&label)
+            ));
+        }
+        out.extend(["  </tfoot>", "</table>"].map(String::from));
+        out.join("\n") + "\n"
+    }
+
+    /// The cells of each line.
+    fn rows(&self) -> Vec<[String; 5]> {
+        self.lines
+            .iter()
+            .map(|line| {
+                [
+                    line.description.clone(),
+                    line.quantity.normalize().to_string(),
+                    line.unit_price.amount().to_string(),
+                    line.discount.amount().to_string(),
+                    line.net.amount().to_string(),
+                ]
+            })
+            .collect()
+    }
+
+    /// The label and amount of each total, omitting zero discounts, shipping and adjustments.
+    fn totals(&self) -> Vec<(String, String)> {
+        let mut totals = vec![("Subtotal".to_string(), self.subtotal.amount().to_string())];
+        for discount in &self.discounts {
+            totals.push((
+                discount.description.clone(),
+                (-discount.amount).amount().to_string(),
+            ));
+        }
+        if !self.shipping.is_zero() {
+            totals.push(("Shipping".to_string(), self.shipping.amount().to_string()));
+        }
+        for tax in &self.taxes {
+            let label = if self.tax_inclusive {
+                format!("{} (included)", tax.name)
+            } else {
+                tax.name.clone()
+            };
+            totals.push((label, tax.amount.amount().to_string()));
+        }
+        if !self.rounding_adjustment.is_zero() {
+            totals.push((
+                "Rounding".to_string(),
+                self.rounding_adjustment.amount().to_string(),
+            ));
+        }
+        totals.push((
+            format!("Total ({:?})", self.currency),
+            self.total.amount().to_string(),
+        ));
+        totals
+    }
+}
+
+const HEADERS: [&str; 5] = [
+    "Description",
+    "Quantity",
+    "Unit price",
+    "Discount",
+    "Amount",
+];
+
+const COLUMN_GAP: &str = "  ";
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::rounding::RoundingMode;
+    use crate::tax::TaxRate;
+    use crate::{cad, usd};
+    use anyhow::Result;
+    use expecting::*;
+    use rust_decimal_macros::dec;
+
+    fn usd(d: &str) -> FractionalMoney {
+        FractionalMoney::new(Decimal::from_str_exact(d).unwrap(), Currency::USD).unwrap()
+    }
+
+    fn cad(d: &str) -> FractionalMoney {
+        FractionalMoney::new(Decimal::from_str_exact(d).unwrap(), Currency::CAD).unwrap()
+    }
+
+    fn groceries() -> Invoice {
+        Invoice::new(Currency::USD)
+            .with_line(InvoiceLine::new("Apples", dec!(3), usd("2.50")))
+            .with_line(InvoiceLine::new("Cheese, 1.5 kg", dec!(1.5), usd("9.999")))
+            .with_tax(SalesTax::new(vec![TaxRate::new("Sales tax", dec!(0.05))]))
+    }
+
+    fn check_consistent(summary: &InvoiceSummary) -> Result<()> {
+        let lines = summary
+            .lines
+            .iter()
+            .try_fold(Money::new(Decimal::ZERO, summary.currency)?, |sum, line| {
+                sum.try_add(&line.net)
+            })?;
+        expect_eq!(lines, summary.subtotal);
+        let mut total = summary
+            .subtotal
+            .try_subtract(&summary.discount)?
+            .try_add(&summary.shipping)?
+            .try_add(&summary.rounding_adjustment)?;
+        if !summary.tax_inclusive {
+            total = total.try_add(&summary.tax)?;
+        }
+        expect_eq!(total, summary.total);
+        Ok(())
+    }
+
+    #[test]
+    fn summary() -> Result<()> {
+        let summary = groceries().summary()?;
+        let amounts: Vec<Money> = summary.lines.iter().map(|line| line.amount).collect();
+        // 1.5 * 9.999 = 14.9985
+        expect_eq!(amounts, vec![usd!(7.50), usd!(15.00)]);
+        expect_eq!(summary.subtotal, usd!(22.50));
+        // 0.375 rounds to 0.38 on the first line.
+        expect_eq!(summary.tax, usd!(1.13));
+        expect_eq!(summary.rounding_adjustment, usd!(0));
+        expect_eq!(summary.total, usd!(23.63));
+        check_consistent(&summary)
+    }
+
+    #[test]
+    fn summary__discounts_shipping_and_cash_rounding() -> Result<()> {
+        let summary = Invoice::new(Currency::CAD)
+            .with_line(InvoiceLine::new("Boots", dec!(2), cad("10")).with_discount(dec!(0.1)))
+            .with_line(InvoiceLine::new("Laces", dec!(1), cad("12")))
+            .with_discount("Coupon", cad!(5))
+            .with_shipping(cad!(4.99), true)
+            .with_tax(SalesTax::new(vec![TaxRate::new("HST", dec!(0.13))]))
+            .with_context(MoneyContext {
+                rounding: RoundingMode::MidpointAwayFromZero,
+                cash_rounding: true,
+                ..Default::default()
+            })
+            .summary()?;
+        expect_eq!(summary.lines[0].discount, cad!(2));
+        expect_eq!(summary.subtotal, cad!(30));
+        expect_eq!(summary.discount, cad!(5));
+        // The coupon is prorated as 3.00 and 2.00, so HST is 1.95 + 1.30 + 0.65 on 15.00, 10.00
+        // and the shipping.
+        expect_eq!(summary.tax, cad!(3.90));
+        expect_eq!(summary.rounding_adjustment, cad!(0.01));
+        expect_eq!(summary.total, cad!(33.90));
+        check_consistent(&summary)
+    }
+
+    #[test]
+    fn summary__untaxed_shipping() -> Result<()> {
+        let summary = groceries().with_shipping(usd!(10), false).summary()?;
+        expect_eq!(summary.tax, usd!(1.13));
+        expect_eq!(summary.total, usd!(33.63));
+        check_consistent(&summary)
+    }
+
+    #[test]
+    fn summary__tax_inclusive() -> Result<()> {
+        let summary = Invoice::new(Currency::CAD)
+            .with_line(InvoiceLine::new("Coffee", dec!(2), cad("2.10")))
+            .with_tax(
+                SalesTax::new(vec![TaxRate::new("GST", dec!(0.05))])
+                    .with_mode(PriceMode::TaxInclusive),
+            )
+            .summary()?;
+        expect_eq!(summary.tax, cad!(0.20));
+        expect_eq!(summary.total, cad!(4.20));
+        expect!(summary.tax_inclusive);
+        check_consistent(&summary)
+    }
+
+    #[test]
+    fn summary__empty() -> Result<()> {
+        let summary = Invoice::new(Currency::USD).summary()?;
+        expect_eq!(summary.total, usd!(0));
+        expect_eq!(summary.total.currency(), Currency::USD);
+        check_consistent(&summary)
+    }
+
+    #[test]
+    fn summary__invalid__returns_err() -> Result<()> {
+        expect_eq!(
+            expect_err!(groceries()
+                .with_line(InvoiceLine::new("Maple syrup", dec!(1), cad("8")))
+                .summary()),
+            Error::MismatchedCurrency
+        );
+        expect_err!(groceries().with_discount("Too much", usd!(23)).summary());
+        expect_err!(groceries()
+            .with_line(InvoiceLine::new("Returned", dec!(-1), usd("1")))
+            .summary());
+        expect_err!(groceries()
+            .with_line(InvoiceLine::new("Free", dec!(1), usd("1")).with_discount(dec!(1.5)))
+            .summary());
+        Ok(())
+    }
+
+    #[test]
+    fn to_text() -> Result<()> {
+        let text = groceries()
+            .with_discount("Coupon", usd!(2))
+            .summary()?
+            .to_text();
+        expect_eq!(
+            text,
+            "\
+Description     Quantity  Unit price  Discount  Amount
+--------------  --------  ----------  --------  ------
+Apples                 3        2.50      0.00    7.50
+Cheese, 1.5 kg       1.5       9.999      0.00   15.00
+
+                                      Subtotal   22.50
+                                        Coupon   -2.00
+                                     Sales tax    1.02
+                                   Total (USD)   21.52
+"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn to_markdown() -> Result<()> {
+        let markdown = Invoice::new(Currency::USD)
+            .with_line(InvoiceLine::new("A | B", dec!(2), usd("1.25")))
+            .summary()?
+            .to_markdown();
+        expect_eq!(
+            markdown,
+            "\
+| Description | Quantity | Unit price | Discount | Amount |
+| :--- | ---: | ---: | ---: | ---: |
+| A \\| B | 2 | 1.25 | 0.00 | 2.50 |
+| **Subtotal** | | | | **2.50** |
+| **Total (USD)** | | | | **2.50** |
+"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn to_html() -> Result<()> {
+        let html = Invoice::new(Currency::USD)
+            .with_line(InvoiceLine::new("<b>Tea</b> & cake", dec!(1), usd("3")))
+            .summary()?
+            .to_html();
+        expect_contains!(html.as_str(), "<td>&lt;b&gt;Tea&lt;/b&gt; &amp; cake</td>");
+        expect_contains!(
+            html.as_str(),
+            "<tr><th colspan=\"4\">Total (USD)</th><td>3.00</td></tr>"
+        );
+        expect!(html.starts_with("<table>\n"));
+        expect!(html.ends_with("</table>\n"));
+        Ok(())
+    }
+}
@@ -0,0 +1,516 @@
+//! Double-entry bookkeeping: accounts, balanced journal entries, balances and trial balances.
+
+pub mod journal;
+
+use crate::currency;
+use crate::currency::Currency;
+use crate::error::Error;
+use crate::money::Money;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// The type of an account, which determines its normal balance.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AccountType {
+    Asset,
+    Liability,
+    Equity,
+    Income,
+    Expense,
+}
+
+impl AccountType {
+    /// The side which increases the account's balance: debits for assets and expenses, and
+    /// credits for liabilities, equity and income.
+    pub fn normal_balance(self) -> Side {
+        match self {
+            Self::Asset | Self::Expense => Side::Debit,
+            Self::Liability | Self::Equity | Self::Income => Side::Credit,
+        }
+    }
+}
+
+impl Display for AccountType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Asset => "asset",
+            Self::Liability => "liability",
+            Self::Equity => "equity",
+            Self::Income => "income",
+            Self::Expense => "expense",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Parses the name of an account type as written by `Display`, e.g., "asset".
+impl FromStr for AccountType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "asset" => Ok(Self::Asset),
+            "liability" => Ok(Self::Liability),
+            "equity" => Ok(Self::Equity),
+            "income" => Ok(Self::Income),
+            "expense" => Ok(Self::Expense),
+            _ => Err(Error::InvalidArgument(format!(
+                "unknown account type \"{s}\""
+            ))),
+        }
+    }
+}
+
+/// The side of a posting.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Side {
+    Debit,
+    Credit,
+}
+
+/// An account in a single currency, e.g., "Assets:Checking" in USD.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Account {
+    pub name: String,
+    pub account_type: AccountType,
+    pub currency: Currency,
+}
+
+/// A debit or credit of a positive amount to an account.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Posting {
+    pub account: String,
+    pub side: Side,
+    pub amount: Money,
+}
+
+impl Posting {
+    pub fn debit(account: impl Into<String>, amount: Money) -> Self {
+        Self {
+            account: account.into(),
+            side: Side::Debit,
+            amount,
+        }
+    }
+
+    pub fn credit(account: impl Into<String>, amount: Money) -> Self {
+        Self {
+            account: account.into(),
+            side: Side::Credit,
+            amount,
+        }
+    }
+}
+
+/// A dated transaction made of postings whose debits and credits must be equal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JournalEntry {
+    pub date: NaiveDate,
+    pub description: String,
+    pub postings: Vec<Posting>,
+}
+
+impl JournalEntry {
+    /// Creates an entry with no postings.
+    pub fn new(date: NaiveDate, description: impl Into<String>) -> Self {
+        Self {
+            date,
+            description: description.into(),
+            postings: vec![],
+        }
+    }
+
+    pub fn with_debit(mut self, account: impl Into<String>, amount: Money) -> Self {
+        self.postings.push(Posting::debit(account, amount));
+        self
+    }
+
+    pub fn with_credit(mut self, account: impl Into<String>, amount: Money) -> Self {
+        self.postings.push(Posting::credit(account, amount));
+        self
+    }
+
+    /// Checks that the entry has postings of positive amounts in a single currency, and that its
+    /// debits equal its credits. Returns the currency.
+    ///
+    /// Returns a `MismatchedCurrency` error if the postings' currencies differ, or an
+    /// `UnbalancedEntry` error if the debits and credits differ.
+    pub fn validate(&self) -> Result<Currency, Error> {
+        if self.postings.is_empty() {
+            return Err(Error::InvalidArgument(format!(
+                "the entry \"{}\" has no postings",
+                self.description
+            )));
+        }
+        let mut currency = Currency::ZeroNone;
+        for posting in &self.postings {
+            currency = currency::combine_currency(currency, posting.amount.currency())?;
+            if !posting.amount.is_positive() {
+                return Err(Error::InvalidArgument(format!(
+                    "expected a positive amount, but the entry \"{}\" posts {} to {}",
+                    self.description, posting.amount, posting.account
+                )));
+            }
+        }
+
+        let zero = Money::new(Decimal::ZERO, currency)?;
+        let (mut debits, mut credits) = (zero, zero);
+        for posting in &self.postings {
+            match posting.side {
+                Side::Debit => debits = debits.try_add(&posting.amount)?,
+                Side::Credit => credits = credits.try_add(&posting.amount)?,
+            }
+        }
+        if debits != credits {
+            return Err(Error::UnbalancedEntry(format!(
+                "the entry \"{}\" has debits of {debits} but credits of {credits}",
+                self.description
+            )));
+        }
+        Ok(currency)
+    }
+}
+
+/// A line of a `TrialBalance`: an account's balance, in the debit or the credit column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrialBalanceLine {
+    pub account: String,
+    pub account_type: AccountType,
+    pub debit: Money,
+    pub credit: Money,
+}
+
+/// The total debits and credits of a `TrialBalance` in one currency.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrialBalanceTotal {
+    pub currency: Currency,
+    pub debits: Money,
+    pub credits: Money,
+}
+
+/// The balance of every account, which should have equal debits and credits in each currency.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrialBalance {
+    /// A line for each account, in the order the accounts were opened.
+    pub lines: Vec<TrialBalanceLine>,
+    /// The totals for each currency, in the order the currencies first appear.
+    pub totals: Vec<TrialBalanceTotal>,
+}
+
+impl TrialBalance {
+    /// True if the debits equal the credits in every currency.
+    pub fn is_balanced(&self) -> bool {
+        self.totals
+            .iter()
+            .all(|total| total.debits == total.credits)
+    }
+}
+
+/// A set of accounts and the journal entries posted to them. Every entry is validated when it is
+/// posted, so the ledger is always balanced.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Ledger {
+    accounts: Vec<Account>,
+    entries: Vec<JournalEntry>,
+}
+
+impl Ledger {
+    /// Creates a ledger with no accounts.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an account. Returns a `DuplicateAccount` error if there is already an account with
+    /// the same name, or an error if the currency is `ZeroNone`.
+    pub fn open_account(
+        &mut self,
+        name: impl Into<String>,
+        account_type: AccountType,
+        currency: Currency,
+    ) -> Result<(), Error> {
+        let name = name.into();
+        if self.account(&name).is_some() {
+            return Err(Error::DuplicateAccount(name));
+        }
+        if currency == Currency::ZeroNone {
+            return Err(Error::InvalidArgument(format!(
+                "the account {name} must have a currency"
+            )));
+        }
+        self.accounts.push(Account {
+            name,
+            account_type,
+            currency,
+        });
+        Ok(())
+    }
+
+    pub fn account(&self, name: &str) -> Option<&Account> {
+        self.accounts.iter().find(|account| account.name == name)
+    }
+
+    pub fn accounts(&self) -> &[Account] {
+        &self.accounts
+    }
+
+    pub fn entries(&self) -> &[JournalEntry] {
+        &self.entries
+    }
+
+    /// Validates and records an entry. Returns an error, and records nothing, if the entry is not
+    /// valid (see `JournalEntry::validate()`), posts to an unknown account, or posts an amount in
+    /// a currency other than the account's.
+    pub fn post(&mut self, entry: JournalEntry) -> Result<(), Error> {
+        let currency = entry.validate()?;
+        for posting in &entry.postings {
+            let account = self
+                .account(&posting.account)
+                .ok_or_else(|| Error::UnknownAccount(posting.account.clone()))?;
+            if account.currency != currency {
+                return Err(Error::MismatchedCurrency);
+            }
+        }
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// The balance of an account on its normal side, e.g., debits minus credits for an asset. A
+    /// negative balance is on the other side, e.g., an overdrawn bank account. Returns an
+    /// `UnknownAccount` error if there is no such account.
+    pub fn balance(&self, name: &str) -> Result<Money, Error> {
+        let account = self
+            .account(name)
+            .ok_or_else(|| Error::UnknownAccount(name.to_string()))?;
+        let (debits, credits) = self.totals(account)?;
+        match account.account_type.normal_balance() {
+            Side::Debit => debits.try_subtract(&credits),
+            Side::Credit => credits.try_subtract(&debits),
+        }
+    }
+
+    /// The balance of every account, in the debit or credit column depending on the side of the
+    /// balance, with totals for each currency.
+    pub fn trial_balance(&self) -> Result<TrialBalance, Error> {
+        let mut lines = vec![];
+        let mut totals: Vec<TrialBalanceTotal> = vec![];
+        for account in &self.accounts {
+            let zero = Money::new(Decimal::ZERO, account.currency)?;
+            let (debits, credits) = self.totals(account)?;
+            let net = debits.try_subtract(&credits)?;
+            let (debit, credit) = if net.is_negative() {
+                (zero, -net)
+            } else {
+                (net, zero)
+            };
+
+            match totals.iter_mut().find(|t| t.currency == account.currency) {
+                Some(total) => {
+                    total.debits = total.debits.try_add(&debit)?;
+                    total.credits = total.credits.try_add(&credit)?;
+                }
+                None => totals.push(TrialBalanceTotal {
+                    currency: account.currency,
+                    debits: debit,
+                    credits: credit,
+                }),
+            }
+            lines.push(TrialBalanceLine {
+                account: account.name.clone(),
+                account_type: account.account_type,
+                debit,
+                credit,
+            });
+        }
+        Ok(TrialBalance { lines, totals })
+    }
+
+    /// The total debits and credits posted to an account.
+    fn totals(&self, account: &Account) -> Result<(Money, Money), Error> {
+        let zero = Money::new(Decimal::ZERO, account.currency)?;
+        let (mut debits, mut credits) = (zero, zero);
+        let postings = self
+            .entries
+            .iter()
+            .flat_map(|entry| &entry.postings)
+            .filter(|posting| posting.account == account.name);
+        for posting in postings {
+            match posting.side {
+                Side::Debit => debits = debits.try_add(&posting.amount)?,
+                Side::Credit => credits = credits.try_add(&posting.amount)?,
+            }
+        }
+        Ok((debits, credits))
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::{cad, usd};
+    use anyhow::Result;
+    use expecting::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, day).unwrap()
+    }
+
+    fn books() -> Result<Ledger> {
+        let mut ledger = Ledger::new();
+        ledger.open_account("Assets:Checking", AccountType::Asset, Currency::USD)?;
+        ledger.open_account("Liabilities:Card", AccountType::Liability, Currency::USD)?;
+        ledger.open_account("Equity:Opening", AccountType::Equity, Currency::USD)?;
+        ledger.open_account("Income:Sales", AccountType::Income, Currency::USD)?;
+        ledger.open_account("Expenses:Rent", AccountType::Expense, Currency::USD)?;
+        ledger.open_account("Assets:Savings", AccountType::Asset, Currency::CAD)?;
+        ledger.open_account("Equity:Opening CAD", AccountType::Equity, Currency::CAD)?;
+
+        ledger.post(
+            JournalEntry::new(date(1), "Opening balance")
+                .with_debit("Assets:Checking", usd!(1000))
+                .with_credit("Equity:Opening", usd!(1000)),
+        )?;
+        ledger.post(
+            JournalEntry::new(date(1), "Opening balance")
+                .with_debit("Assets:Savings", cad!(500))
+                .with_credit("Equity:Opening CAD", cad!(500)),
+        )?;
+        ledger.post(
+            JournalEntry::new(date(5), "Invoice 17")
+                .with_debit("Assets:Checking", usd!(250))
+                .with_credit("Income:Sales", usd!(250)),
+        )?;
+        ledger.post(
+            JournalEntry::new(date(31), "January rent")
+                .with_debit("Expenses:Rent", usd!(1500))
+                .with_credit("Assets:Checking", usd!(1200))
+                .with_credit("Liabilities:Card", usd!(300)),
+        )?;
+        Ok(ledger)
+    }
+
+    #[test]
+    fn balance() -> Result<()> {
+        let ledger = books()?;
+        expect_eq!(ledger.balance("Assets:Checking")?, usd!(50));
+        expect_eq!(ledger.balance("Liabilities:Card")?, usd!(300));
+        expect_eq!(ledger.balance("Income:Sales")?, usd!(250));
+        expect_eq!(ledger.balance("Expenses:Rent")?, usd!(1500));
+        expect_eq!(ledger.balance("Assets:Savings")?, cad!(500));
+        expect_eq!(
+            expect_err!(ledger.balance("Assets:Cash")),
+            Error::UnknownAccount("Assets:Cash".to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn balance__negative() -> Result<()> {
+        let mut ledger = books()?;
+        ledger.post(
+            JournalEntry::new(date(31), "Overdraft")
+                .with_debit("Expenses:Rent", usd!(100))
+                .with_credit("Assets:Checking", usd!(100)),
+        )?;
+        expect_eq!(ledger.balance("Assets:Checking")?, usd!(-50));
+        Ok(())
+    }
+
+    #[test]
+    fn trial_balance() -> Result<()> {
+        let trial_balance = books()?.trial_balance()?;
+        expect!(trial_balance.is_balanced());
+        expect_eq!(trial_balance.lines.len(), 7);
+        expect_eq!(
+            trial_balance.lines[1].clone(),
+            TrialBalanceLine {
+                account: "Liabilities:Card".to_string(),
+                account_type: AccountType::Liability,
+                debit: usd!(0),
+                credit: usd!(300),
+            }
+        );
+        expect_eq!(
+            trial_balance.totals,
+            vec![
+                TrialBalanceTotal {
+                    currency: Currency::USD,
+                    debits: usd!(1550),
+                    credits: usd!(1550),
+                },
+                TrialBalanceTotal {
+                    currency: Currency::CAD,
+                    debits: cad!(500),
+                    credits: cad!(500),
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn open_account__duplicate__returns_err() -> Result<()> {
+        let mut ledger = books()?;
+        expect_eq!(
+            expect_err!(ledger.open_account("Income:Sales", AccountType::Income, Currency::CAD)),
+            Error::DuplicateAccount("Income:Sales".to_string())
+        );
+        expect_err!(ledger.open_account("Assets:Nothing", AccountType::Asset, Currency::ZeroNone));
+        Ok(())
+    }
+
+    #[test]
+    fn post__unbalanced__returns_err() -> Result<()> {
+        let mut ledger = books()?;
+        let entry = JournalEntry::new(date(2), "Typo")
+            .with_debit("Assets:Checking", usd!(10))
+            .with_credit("Income:Sales", usd!(1));
+        expect_eq!(
+            expect_err!(ledger.post(entry)),
+            Error::UnbalancedEntry(
+                "the entry \"Typo\" has debits of 10.00 USD but credits of 1.00 USD".to_string()
+            )
+        );
+        expect_eq!(ledger.entries().len(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn post__mixed_currency__returns_err() -> Result<()> {
+        let mut ledger = books()?;
+        let entry = JournalEntry::new(date(2), "Transfer")
+            .with_debit("Assets:Savings", cad!(10))
+            .with_credit("Assets:Checking", usd!(10));
+        expect_eq!(expect_err!(ledger.post(entry)), Error::MismatchedCurrency);
+
+        // A balanced entry in a currency other than the accounts'.
+        let entry = JournalEntry::new(date(2), "Sale")
+            .with_debit("Assets:Checking", cad!(10))
+            .with_credit("Income:Sales", cad!(10));
+        expect_eq!(expect_err!(ledger.post(entry)), Error::MismatchedCurrency);
+        Ok(())
+    }
+
+    #[test]
+    fn post__invalid__returns_err() -> Result<()> {
+        let mut ledger = books()?;
+        expect_eq!(
+            expect_err!(ledger.post(
+                JournalEntry::new(date(2), "Sale")
+                    .with_debit("Assets:Cash", usd!(10))
+                    .with_credit("Income:Sales", usd!(10))
+            )),
+            Error::UnknownAccount("Assets:Cash".to_string())
+        );
+        expect_err!(ledger.post(JournalEntry::new(date(2), "Nothing")));
+        expect_err!(ledger.post(
+            JournalEntry::new(date(2), "Refund")
+                .with_debit("Assets:Checking", usd!(-10))
+                .with_credit("Income:Sales", usd!(-10))
+        ));
+        expect_eq!(ledger.entries().len(), 4);
+        Ok(())
+    }
+}
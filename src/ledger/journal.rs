@@ -0,0 +1,729 @@
+//! A plain-text journal format for ledgers, similar to that of Ledger and hledger:
+//!
+//! ```text
+//! ; Comments and blank lines between transactions are kept.
+//! account Assets:Checking  asset USD
+//! account Income:Sales  income USD
+//!
+//! 2024-01-05 Invoice 17
+//!     Assets:Checking   250.00 USD
+//!     Income:Sales     -250.00 USD
+//! ```
+//!
+//! An `account` line declares an account's name, type and currency, and every account posted to
+//! must be declared. A transaction is a date and a description, followed by indented postings of
+//! an account and an amount, separated by at least two spaces or a tab. Positive amounts are debits
+//! and negative amounts are credits.
+//!
+//! Writing a journal produces its canonical form, with the postings' amounts aligned, so parsing
+//! and writing a canonical journal reproduces it exactly.
+
+use crate::currency::Currency;
+use crate::error::Error;
+use crate::ledger::{Account, JournalEntry, Ledger, Posting, Side};
+use crate::money::Money;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+const INDENT: &str = "    ";
+
+/// A top-level item of a `Journal`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum JournalItem {
+    /// A line starting with `;` or `#`, including the marker.
+    Comment(String),
+    Blank,
+    Account(Account),
+    Entry(JournalEntry),
+}
+
+/// A parsed journal file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Journal {
+    pub items: Vec<JournalItem>,
+}
+
+impl Journal {
+    /// Parses a journal. Amounts are validated like `Money::new()`, every transaction must
+    /// balance (see `JournalEntry::validate()`), and accounts must be declared once, before or
+    /// after the transactions which post to them, and posted to in their currency.
+    ///
+    /// Returns a `Parse` error with the line and column of a problem. The lines are checked in
+    /// order, but since an account may be declared after it is used, postings to undeclared
+    /// accounts or in the wrong currency are only reported if every line is otherwise valid.
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let mut items = vec![];
+        // The transaction being parsed, and the number of its first line.
+        let mut current: Option<(usize, JournalEntry)> = None;
+        let mut currencies = HashMap::new();
+        // Where each posting is, for checking its account once every account has been declared.
+        let mut locations = vec![];
+
+        for (index, line) in text.lines().enumerate() {
+            let number = index + 1;
+            let line = line.trim_end();
+            if line.starts_with([' ', '\t']) {
+                let Some((_, entry)) = current.as_mut() else {
+                    return Err(parse_error(number, 1, "unexpected indented line"));
+                };
+                let (posting, location) = parse_posting(number, line)?;
+                entry.postings.push(posting);
+                locations.push(location);
+                continue;
+            }
+
+            finish(&mut items, current.take())?;
+            if line.is_empty() {
+                items.push(JournalItem::Blank);
+            } else if line.starts_with([';', '#']) {
+                items.push(JournalItem::Comment(line.to_string()));
+            } else if line.starts_with("account ") {
+                let (account, name_column) = parse_account(number, line)?;
+                if currencies
+                    .insert(account.name.clone(), account.currency)
+                    .is_some()
+                {
+                    let error = Error::DuplicateAccount(account.name);
+                    return Err(parse_error(number, name_column, error.to_string()));
+                }
+                items.push(JournalItem::Account(account));
+            } else {
+                current = Some((number, parse_header(number, line)?));
+            }
+        }
+        finish(&mut items, current.take())?;
+
+        let journal = Self { items };
+        let postings = journal.entries().flat_map(|entry| &entry.postings);
+        for (posting, location) in postings.zip(locations) {
+            let Some(&currency) = currencies.get(&posting.account) else {
+                let error = Error::UnknownAccount(posting.account.clone());
+                return Err(parse_error(
+                    location.line,
+                    location.account,
+                    error.to_string(),
+                ));
+            };
+            if posting.amount.currency() != currency {
+                return Err(parse_error(
+                    location.line,
+                    location.currency,
+                    format!(
+                        "expected an amount in {currency:?}, the currency of {}",
+                        posting.account
+                    ),
+                ));
+            }
+        }
+        Ok(journal)
+    }
+
+    /// Creates a journal of a ledger's accounts followed by its entries, separated by blank lines.
+    /// Returns an `InvalidArgument` error if an account name or a description cannot be written
+    /// so that it parses back the same, e.g., because it contains a line break or two spaces in a
+    /// row.
+    pub fn from_ledger(ledger: &Ledger) -> Result<Self, Error> {
+        for account in ledger.accounts() {
+            check_account_name(&account.name)?;
+        }
+        for entry in ledger.entries() {
+            check_description(&entry.description)?;
+        }
+        let mut items: Vec<JournalItem> = ledger
+            .accounts()
+            .iter()
+            .cloned()
+            .map(JournalItem::Account)
+            .collect();
+        for entry in ledger.entries() {
+            if !items.is_empty() {
+                items.push(JournalItem::Blank);
+            }
+            items.push(JournalItem::Entry(entry.clone()));
+        }
+        Ok(Self { items })
+    }
+
+    /// Creates a ledger of the journal's accounts and entries. Returns an error if an account is
+    /// declared twice, or an entry posts to an undeclared account or in another currency, which
+    /// `parse()` has already checked (with the location of the problem) for a parsed journal.
+    pub fn to_ledger(&self) -> Result<Ledger, Error> {
+        let mut ledger = Ledger::new();
+        for account in self.accounts() {
+            ledger.open_account(account.name.clone(), account.account_type, account.currency)?;
+        }
+        for entry in self.entries() {
+            ledger.post(entry.clone())?;
+        }
+        Ok(ledger)
+    }
+
+    pub fn accounts(&self) -> impl Iterator<Item = &Account> {
+        self.items.iter().filter_map(|item| match item {
+            JournalItem::Account(account) => Some(account),
+            _ => None,
+        })
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &JournalEntry> {
+        self.items.iter().filter_map(|item| match item {
+            JournalItem::Entry(entry) => Some(entry),
+            _ => None,
+        })
+    }
+}
+
+impl FromStr for Journal {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Writes the journal in its canonical form. Items are written as they are, so a journal built
+/// by hand, rather than with `parse()` or `from_ledger()`, may not parse back.
+impl Display for Journal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for item in &self.items {
+            match item {
+                JournalItem::Comment(comment) => writeln!(f, "{comment}")?,
+                JournalItem::Blank => writeln!(f)?,
+                JournalItem::Account(account) => writeln!(
+                    f,
+                    "account {}  {} {:?}",
+                    account.name, account.account_type, account.currency
+                )?,
+                JournalItem::Entry(entry) => write_entry(f, entry)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_entry(f: &mut Formatter<'_>, entry: &JournalEntry) -> std::fmt::Result {
+    let date = entry.date.format(DATE_FORMAT);
+    if entry.description.is_empty() {
+        writeln!(f, "{date}")?;
+    } else {
+        writeln!(f, "{date} {}", entry.description)?;
+    }
+
+    let amounts: Vec<String> = entry
+        .postings
+        .iter()
+        .map(|posting| {
+            let amount = match posting.side {
+                Side::Debit => posting.amount,
+                Side::Credit => -posting.amount,
+            };
+            format!("{} {:?}", amount.amount(), amount.currency())
+        })
+        .collect();
+    let account_width = entry
+        .postings
+        .iter()
+        .map(|posting| posting.account.chars().count())
+        .max()
+        .unwrap_or(0);
+    let amount_width = amounts.iter().map(String::len).max().unwrap_or(0);
+    for (posting, amount) in entry.postings.iter().zip(amounts) {
+        writeln!(
+            f,
+            "{INDENT}{:<account_width$}  {amount:>amount_width$}",
+            posting.account
+        )?;
+    }
+    Ok(())
+}
+
+/// Checks that an account name is words separated by single spaces, which doesn't look like a
+/// comment.
+fn check_account_name(name: &str) -> Result<(), Error> {
+    let valid = !name.starts_with([';', '#'])
+        && name.split(' ').all(|word| {
+            !word.is_empty() && !word.contains(|c: char| c.is_whitespace() || c.is_control())
+        });
+    if !valid {
+        return Err(Error::InvalidArgument(format!(
+            "the account name {name:?} cannot be written to a journal, as it is not made of \
+             words separated by single spaces"
+        )));
+    }
+    Ok(())
+}
+
+/// Checks that a description is a single line, without leading or trailing whitespace.
+fn check_description(description: &str) -> Result<(), Error> {
+    if description.contains(char::is_control) || description.trim() != description {
+        return Err(Error::InvalidArgument(format!(
+            "the description {description:?} cannot be written to a journal, as it is not a \
+             single line without leading or trailing whitespace"
+        )));
+    }
+    Ok(())
+}
+
+/// Validates a parsed transaction and adds it to the items.
+fn finish(
+    items: &mut Vec<JournalItem>,
+    current: Option<(usize, JournalEntry)>,
+) -> Result<(), Error> {
+    if let Some((number, entry)) = current {
+        entry
+            .validate()
+            .map_err(|error| parse_error(number, 1, error.to_string()))?;
+        items.push(JournalItem::Entry(entry));
+    }
+    Ok(())
+}
+
+/// Parses the first line of a transaction: a date and a description, separated by a space or a
+/// tab.
+fn parse_header(number: usize, line: &str) -> Result<JournalEntry, Error> {
+    let (date, description) = line.split_once([' ', '\t']).unwrap_or((line, ""));
+    let date = NaiveDate::parse_from_str(date, DATE_FORMAT).map_err(|_| {
+        parse_error(
+            number,
+            1,
+            format!("expected a date in the form YYYY-MM-DD, but got \"{date}\""),
+        )
+    })?;
+    Ok(JournalEntry::new(date, description.trim_start()))
+}
+
+/// Parses an account declaration, e.g., "account Assets:Checking  asset USD", and returns it
+/// with the column of its name.
+fn parse_account(number: usize, line: &str) -> Result<(Account, usize), Error> {
+    let name_start = line.len() - line["account ".len()..].trim_start().len();
+    let (name, rest) = split_fields(number, line, "account ".len(), "account type")?;
+    let mut fields = words(line, rest);
+    let (Some((type_start, account_type)), Some((currency_start, currency)), None) =
+        (fields.next(), fields.next(), fields.next())
+    else {
+        return Err(parse_error(
+            number,
+            column(line, rest),
+            "expected an account type and a currency, e.g., \"asset USD\"",
+        ));
+    };
+    let account = Account {
+        name: name.to_string(),
+        account_type: account_type
+            .parse()
+            .map_err(|error: Error| at(number, line, type_start, error))?,
+        currency: currency
+            .parse()
+            .map_err(|error: Error| at(number, line, currency_start, error))?,
+    };
+    Ok((account, column(line, name_start)))
+}
+
+/// The line number of a posting, and the columns of its account and currency.
+struct PostingLocation {
+    line: usize,
+    account: usize,
+    currency: usize,
+}
+
+/// Parses an indented posting, e.g., "    Assets:Checking  13.37 USD".
+fn parse_posting(number: usize, line: &str) -> Result<(Posting, PostingLocation), Error> {
+    let indent = line.len() - line.trim_start().len();
+    if line[indent..].starts_with([';', '#']) {
+        return Err(parse_error(
+            number,
+            column(line, indent),
+            "comments are only supported between transactions",
+        ));
+    }
+    let (account, rest) = split_fields(number, line, indent, "amount")?;
+    let mut fields = words(line, rest);
+    let (Some((amount_start, amount)), Some((currency_start, currency)), None) =
+        (fields.next(), fields.next(), fields.next())
+    else {
+        return Err(parse_error(
+            number,
+            column(line, rest),
+            "expected an amount and a currency, e.g., \"13.37 USD\"",
+        ));
+    };
+
+    let amount = Decimal::from_str_exact(amount).map_err(|_| {
+        parse_error(
+            number,
+            column(line, amount_start),
+            format!("invalid amount \"{amount}\""),
+        )
+    })?;
+    let currency: Currency = currency
+        .parse()
+        .map_err(|error: Error| at(number, line, currency_start, error))?;
+    let money = Money::new(amount.abs(), currency)
+        .map_err(|error| at(number, line, amount_start, error))?;
+    if money.is_zero() {
+        return Err(parse_error(
+            number,
+            column(line, amount_start),
+            "expected a non-zero amount",
+        ));
+    }
+
+    let posting = if amount.is_sign_negative() {
+        Posting::credit(account, money)
+    } else {
+        Posting::debit(account, money)
+    };
+    let location = PostingLocation {
+        line: number,
+        account: column(line, indent),
+        currency: column(line, currency_start),
+    };
+    Ok((posting, location))
+}
+
+/// Splits the text of a line from `start` into a name (which may contain single spaces) and the
+/// byte offset of the fields after it, which are separated from it by two spaces or a tab.
+fn split_fields<'a>(
+    number: usize,
+    line: &'a str,
+    start: usize,
+    expected: &str,
+) -> Result<(&'a str, usize), Error> {
+    let text = &line[start..];
+    let separator = [text.find("  "), text.find('\t')]
+        .into_iter()
+        .flatten()
+        .min()
+        .ok_or_else(|| {
+            parse_error(
+                number,
+                column(line, line.len()),
+                format!("expected an {expected} after two spaces or a tab"),
+            )
+        })?;
+    let name = text[..separator].trim();
+    if name.is_empty() {
+        return Err(parse_error(
+            number,
+            column(line, start),
+            "expected an account name",
+        ));
+    }
+    let rest = &text[separator..];
+    Ok((
+        name,
+        start + separator + rest.len() - rest.trim_start().len(),
+    ))
+}
+
+/// The whitespace-separated words of a line from `start`, with their byte offsets.
+fn words(line: &str, start: usize) -> impl Iterator<Item = (usize, &str)> {
+    let mut offset = start;
+    line[start..].split([' ', '\t']).filter_map(move |word| {
+        let word_start = offset;
+        offset += word.len() + 1;
+        (!word.is_empty()).then_some((word_start, word))
+    })
+}
+
+/// The one-based column of a byte offset in a line.
+fn column(line: &str, offset: usize) -> usize {
+    line[..offset].chars().count() + 1
+}
+
+fn at(number: usize, line: &str, offset: usize, error: Error) -> Error {
+    parse_error(number, column(line, offset), error.to_string())
+}
+
+fn parse_error(line: usize, column: usize, message: impl Into<String>) -> Error {
+    Error::Parse {
+        line,
+        column,
+        message: message.into(),
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::ledger::AccountType;
+    use crate::{cad, usd};
+    use anyhow::Result;
+    use expecting::*;
+
+    const JOURNAL: &str = "\
+; Household books
+account Assets:Checking  asset USD
+account Liabilities:Card  liability USD
+account Equity:Opening  equity USD
+account Expenses:Rent  expense USD
+# Savings are in Canadian dollars.
+account Assets:Savings  asset CAD
+account Equity:Opening CAD  equity CAD
+
+2024-01-01 Opening balance
+    Assets:Checking   1000.00 USD
+    Equity:Opening   -1000.00 USD
+
+2024-01-01 Opening balance
+    Assets:Savings       500.00 CAD
+    Equity:Opening CAD  -500.00 CAD
+
+2024-01-31 January rent
+    Expenses:Rent      1500.00 USD
+    Assets:Checking   -1200.00 USD
+    Liabilities:Card   -300.00 USD
+";
+
+    fn parse_err(text: &str) -> (usize, usize, String) {
+        match Journal::parse(text) {
+            Err(Error::Parse {
+                line,
+                column,
+                message,
+            }) => (line, column, message),
+            result => panic!("expected a parse error, but got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn parse() -> Result<()> {
+        let journal = Journal::parse(JOURNAL)?;
+        expect_eq!(journal.items.len(), 14);
+        expect_eq!(
+            journal.items[0].clone(),
+            JournalItem::Comment("; Household books".to_string())
+        );
+        expect_eq!(journal.accounts().count(), 6);
+
+        let entries: Vec<&JournalEntry> = journal.entries().collect();
+        expect_eq!(entries.len(), 3);
+        expect_eq!(
+            entries[2].clone(),
+            JournalEntry::new(
+                NaiveDate::from_ymd_opt(2024, 1, 31).unwrap(),
+                "January rent"
+            )
+            .with_debit("Expenses:Rent", usd!(1500))
+            .with_credit("Assets:Checking", usd!(1200))
+            .with_credit("Liabilities:Card", usd!(300))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn to_string__round_trips() -> Result<()> {
+        expect_eq!(Journal::parse(JOURNAL)?.to_string(), JOURNAL);
+        Ok(())
+    }
+
+    #[test]
+    fn to_string__canonical() -> Result<()> {
+        let text = "\
+account Assets:Cash\tasset   CAD
+account Income:Tips  income CAD
+2024-03-01   Tips  \t
+\tAssets:Cash\t12.50 CAD
+  Income:Tips    -12.50   CAD
+";
+        let journal = Journal::parse(text)?;
+        expect_eq!(
+            journal.to_string(),
+            "\
+account Assets:Cash  asset CAD
+account Income:Tips  income CAD
+2024-03-01 Tips
+    Assets:Cash   12.50 CAD
+    Income:Tips  -12.50 CAD
+"
+        );
+        expect_eq!(Journal::parse(&journal.to_string())?, journal);
+
+        let journal = Journal::parse(&text.replace("2024-03-01   Tips", "2024-03-01\tTips"))?;
+        expect_eq!(
+            journal
+                .entries()
+                .next()
+                .map(|entry| entry.description.as_str()),
+            Some("Tips")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn to_ledger() -> Result<()> {
+        let ledger = Journal::parse(JOURNAL)?.to_ledger()?;
+        expect_eq!(ledger.balance("Assets:Checking")?, usd!(-200));
+        expect_eq!(ledger.balance("Assets:Savings")?, cad!(500));
+        expect!(ledger.trial_balance()?.is_balanced());
+
+        let journal = Journal::from_ledger(&ledger)?;
+        expect_eq!(Journal::parse(&journal.to_string())?.to_ledger()?, ledger);
+        Ok(())
+    }
+
+    #[test]
+    fn from_ledger__round_trips() -> Result<()> {
+        let mut ledger = Ledger::new();
+        ledger.open_account("Assets:Caisse populaire", AccountType::Asset, Currency::CAD)?;
+        ledger.open_account("Revenus:Ventes #2", AccountType::Income, Currency::CAD)?;
+        ledger.post(
+            JournalEntry::new(NaiveDate::from_ymd_opt(2024, 5, 1).unwrap(), "Vente  ; 42")
+                .with_debit("Assets:Caisse populaire", cad!(80.05))
+                .with_credit("Revenus:Ventes #2", cad!(80.05)),
+        )?;
+        ledger.post(
+            JournalEntry::new(NaiveDate::from_ymd_opt(2024, 5, 2).unwrap(), "")
+                .with_debit("Revenus:Ventes #2", cad!(0.05))
+                .with_credit("Assets:Caisse populaire", cad!(0.05)),
+        )?;
+        let text = Journal::from_ledger(&ledger)?.to_string();
+        expect_eq!(Journal::parse(&text)?.to_ledger()?, ledger);
+        Ok(())
+    }
+
+    #[test]
+    fn from_ledger__unrepresentable__returns_err() -> Result<()> {
+        for name in [
+            "Assets:A  B",
+            "Assets:A\tB",
+            " Assets:A",
+            "Assets:A ",
+            "; Assets",
+            "",
+        ] {
+            let mut ledger = Ledger::new();
+            ledger.open_account(name, AccountType::Asset, Currency::USD)?;
+            expect_err!(Journal::from_ledger(&ledger));
+        }
+
+        let date = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        for description in ["line1\n; x", " Sale", "Sale ", "Sale\r"] {
+            let mut ledger = Ledger::new();
+            ledger.open_account("Assets:Cash", AccountType::Asset, Currency::USD)?;
+            ledger.open_account("Income:Sales", AccountType::Income, Currency::USD)?;
+            ledger.post(
+                JournalEntry::new(date, description)
+                    .with_debit("Assets:Cash", usd!(1))
+                    .with_credit("Income:Sales", usd!(1)),
+            )?;
+            expect_err!(Journal::from_ledger(&ledger));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn parse__accounts__returns_err() -> Result<()> {
+        let (line, column, message) = parse_err(
+            "\
+account Assets:Cash  asset USD
+
+2024-01-01 Coffee
+    Expenses:Coffee  3.50 USD
+    Assets:Cash     -3.50 USD
+",
+        );
+        expect_eq!((line, column), (4, 5));
+        expect_eq!(message, "Unknown account: Expenses:Coffee");
+
+        let (line, column, message) = parse_err(
+            "\
+account Assets:Cash  asset USD
+account Income:Tips  income USD
+account  Assets:Cash  asset CAD
+",
+        );
+        expect_eq!((line, column), (3, 10));
+        expect_eq!(message, "Duplicate account: Assets:Cash");
+
+        let (line, column, message) = parse_err(
+            "\
+2024-03-01 Tips
+    Assets:Cash   12.50 CAD
+    Income:Tips  -12.50 CAD
+
+account Assets:Cash  asset CAD
+account Income:Tips  income USD
+",
+        );
+        expect_eq!((line, column), (3, 25));
+        expect_eq!(
+            message,
+            "expected an amount in USD, the currency of Income:Tips"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse__invalid_amount__returns_err() -> Result<()> {
+        let (line, column, message) = parse_err(
+            "2024-01-01 Coffee\n    Expenses:Coffee  3.505 USD\n    Assets:Cash  -3.505 USD\n",
+        );
+        expect_eq!((line, column), (2, 22));
+        expect_contains!(message.as_str(), "Invalid money value");
+
+        let (line, column, _) = parse_err("2024-01-01 Coffee\n    Expenses:Coffee  3.5O USD\n");
+        expect_eq!((line, column), (2, 22));
+
+        let (line, column, message) =
+            parse_err("2024-01-01 Coffee\n    Expenses:Coffee  3.50 EUR\n");
+        expect_eq!((line, column), (2, 27));
+        expect_eq!(message, "Unknown currency: EUR");
+
+        let (line, column, _) = parse_err("2024-01-01 Coffee\n    Expenses:Coffee 3.50 USD\n");
+        expect_eq!((line, column), (2, 29));
+
+        let (line, column, _) = parse_err("2024-01-01 Coffee\n    Expenses:Coffee  0 USD\n");
+        expect_eq!((line, column), (2, 22));
+        Ok(())
+    }
+
+    #[test]
+    fn parse__unbalanced__returns_err() -> Result<()> {
+        let (line, column, message) = parse_err(
+            "\
+; Lunch
+
+2024-01-02 Lunch
+    Expenses:Food  12.00 USD
+    Assets:Cash  -10.00 USD
+",
+        );
+        expect_eq!((line, column), (3, 1));
+        expect_contains!(message.as_str(), "Unbalanced journal entry");
+
+        let (line, _, message) = parse_err(
+            "2024-01-02 Lunch\n    Expenses:Food  12.00 USD\n    Assets:Cash  -12.00 CAD\n",
+        );
+        expect_eq!(line, 1);
+        expect_contains!(message.as_str(), "different currencies");
+        Ok(())
+    }
+
+    #[test]
+    fn parse__invalid_lines__returns_err() -> Result<()> {
+        expect_eq!(parse_err("2024-13-01 Lunch\n").0, 1);
+        expect_eq!(parse_err("\n    Assets:Cash  1.00 USD\n").0, 2);
+        expect_eq!(
+            parse_err("2024-01-02 Lunch\n    ; note\n"),
+            (
+                2,
+                5,
+                "comments are only supported between transactions".to_string()
+            )
+        );
+        expect_eq!(parse_err("account Assets:Cash  asset\n").0, 1);
+        let (line, column, message) = parse_err("account Assets:Cash  cash USD\n");
+        expect_eq!((line, column), (1, 22));
+        expect_contains!(message.as_str(), "unknown account type");
+        // A transaction with no postings.
+        expect_eq!(parse_err("2024-01-02 Nothing\n\n").0, 1);
+        Ok(())
+    }
+}
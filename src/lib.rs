@@ -1,13 +1,60 @@
 //! Cashmoney is a library for expressing monetary values and performing safe
 //! monetary calculations suitable for financial applications.
 
+mod amortization;
+mod brackets;
+mod context;
 mod currency;
+mod depreciation;
 mod error;
+mod fees;
+mod formula;
 mod fractional_money;
+mod interest;
+mod invoice;
+mod ledger;
 mod macros;
 mod money;
+mod money_expr;
+mod pricing;
+mod proration;
+mod rational_money;
+mod rounding;
+mod tax;
+mod traced_money;
+mod tvm;
+mod unit_price;
 
+pub use crate::amortization::{Loan, LoanKind, PaymentFrequency, Period, Schedule};
+pub use crate::brackets::{
+    Bracket, BracketMode, BracketResult, BracketSchedule, BracketSlice, Tier,
+};
+pub use crate::context::{MoneyContext, OverflowPolicy};
 pub use crate::currency::Currency;
+pub use crate::depreciation::{Asset, DepreciationPeriod, DepreciationSchedule};
 pub use crate::error::Error;
+pub use crate::fees::{FeeComponent, FeeSchedule};
+pub use crate::formula::{evaluate_formula, Formula, FormulaValue};
 pub use crate::fractional_money::FractionalMoney;
+pub use crate::interest::{Compounding, DayCount, Interest, InterestRate};
+pub use crate::invoice::{Invoice, InvoiceDiscount, InvoiceLine, InvoiceLineTotal, InvoiceSummary};
+pub use crate::ledger::journal::{Journal, JournalItem};
+pub use crate::ledger::{
+    Account, AccountType, JournalEntry, Ledger, Posting, Side, TrialBalance, TrialBalanceLine,
+    TrialBalanceTotal,
+};
 pub use crate::money::Money;
+pub use crate::money_expr::MoneyExpr;
+pub use crate::pricing::{
+    Discount, LineAdjustment, LineItem, PricedLine, PricedOrder, Pricing, Promotion,
+};
+pub use crate::proration::{ProratedChange, Proration, ProrationMethod};
+pub use crate::rational_money::RationalMoney;
+pub use crate::rounding::{round_all, RoundedLine, RoundingAccumulator, RoundingMode};
+pub use crate::tax::canada::{Province, RateEntry, RateTable};
+pub use crate::tax::{
+    InvoiceTax, PriceMode, RoundingLevel, SalesTax, TaxAmount, TaxBreakdown, TaxRate,
+};
+pub use crate::traced_money::{Operation, Step, TracedMoney};
+pub use crate::tvm::{fv, irr, nper, npv, pmt, pv, xirr, xnpv, CashFlow, PaymentTiming};
+pub use crate::unit_price::{Quantity, Unit, UnitPrice};
@@ -1,3 +1,4 @@
+use crate::currency;
 use crate::currency::Currency;
 use crate::error::Error;
 use crate::fractional_money::FractionalMoney;
@@ -5,7 +6,7 @@ use rust_decimal::Decimal;
 use std::cmp::Ordering;
 use std::fmt::{Display, Formatter};
 use std::iter;
-use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Rem, Sub, SubAssign};
 
 /// A monetary value in a certain currency with a valid denomination, e.g., 13.37 USD but not
 /// 1.337 USD.
@@ -28,6 +29,14 @@ impl Money {
         })
     }
 
+    /// Creates a new monetary value from an amount in the minor units of the currency, e.g., 1337
+    /// with `Currency::USD` is 13.37 USD.
+    pub fn from_minor_units(units: i128, currency: Currency) -> Result<Self, Error> {
+        let amount = Decimal::try_from_i128_with_scale(units, currency.max_precision())
+            .map_err(|_| Error::Overflow)?;
+        Self::new(amount, currency)
+    }
+
     pub(crate) fn new_unchecked(money: FractionalMoney) -> Self {
         Money { money }
     }
@@ -44,6 +53,19 @@ impl Money {
         self.money.currency()
     }
 
+    /// Returns the amount in the minor units of the currency, e.g., 13.37 USD is 1337 cents.
+    pub fn minor_units(&self) -> i128 {
+        // An amount too large to rescale has fewer decimal places than the currency, so scale the
+        // mantissa up rather than assuming it is already in minor units. The mantissa has at most
+        // 96 bits, so this cannot overflow an i128.
+        let amount = self.amount();
+        let shift = self
+            .currency()
+            .max_precision()
+            .saturating_sub(amount.scale());
+        amount.mantissa() * 10_i128.pow(shift)
+    }
+
     /// Attempts to add another monetary value to this one. Returns an error if the currencies do
     /// not match.
     pub fn try_add(&self, rhs: &Self) -> Result<Self, Error> {
@@ -60,6 +82,123 @@ impl Money {
         })
     }
 
+    /// Attempts to add a fractional monetary value to this one, resulting in `FractionalMoney`.
+    /// Returns an error if the currencies do not match.
+    pub fn try_add_fractional(&self, rhs: &FractionalMoney) -> Result<FractionalMoney, Error> {
+        self.money.try_add(rhs)
+    }
+
+    /// Attempts to subtract a fractional monetary value from this one, resulting in
+    /// `FractionalMoney`. Returns an error if the currencies do not match.
+    pub fn try_subtract_fractional(&self, rhs: &FractionalMoney) -> Result<FractionalMoney, Error> {
+        self.money.try_subtract(rhs)
+    }
+
+    /// Returns the ratio of this monetary value to another, e.g., 5.00 USD / 20.00 USD is 0.25.
+    /// Returns an error if the currencies do not match or if `other` is zero.
+    pub fn try_ratio(&self, other: &Self) -> Result<Decimal, Error> {
+        currency::combine_currency(self.currency(), other.currency())?;
+        if other.is_zero() {
+            return Err(Error::DivisionByZero);
+        }
+        self.amount()
+            .checked_div(other.amount())
+            .ok_or(Error::Overflow)
+    }
+
+    /// Divides this monetary value by `divisor` in minor units, returning the quotient and the
+    /// remainder such that `quotient * divisor + remainder == self`. For example, 10.00 USD divided
+    /// by 3 is 3.33 USD with a remainder of 0.01 USD. The remainder has the same sign as `self`.
+    pub fn div_rem(&self, divisor: i64) -> Result<(Self, Self), Error> {
+        if divisor == 0 {
+            return Err(Error::DivisionByZero);
+        }
+        let units = self.minor_units();
+        let divisor = i128::from(divisor);
+        let quotient = units.checked_div(divisor).ok_or(Error::Overflow)?;
+        let remainder = units.checked_rem(divisor).ok_or(Error::Overflow)?;
+
+        Ok((
+            Self::from_minor_units(quotient, self.currency())?,
+            Self::from_minor_units(remainder, self.currency())?,
+        ))
+    }
+
+    /// Attempts to compute the remainder of dividing this monetary value by another, e.g.,
+    /// 10.00 USD % 3.00 USD is 1.00 USD. Returns an error if the currencies do not match or if
+    /// `rhs` is zero.
+    pub fn try_rem(&self, rhs: &Self) -> Result<Self, Error> {
+        let currency = currency::combine_currency(self.currency(), rhs.currency())?;
+        if rhs.is_zero() {
+            return Err(Error::DivisionByZero);
+        }
+        let remainder = self
+            .minor_units()
+            .checked_rem(rhs.minor_units())
+            .ok_or(Error::Overflow)?;
+
+        Self::from_minor_units(remainder, currency)
+    }
+
+    /// Allocates this monetary value into parts proportional to `ratios` without losing or creating
+    /// any minor units, e.g., 10.00 USD allocated by `[1, 1, 1]` is 3.34, 3.33 and 3.33 USD. This
+    /// uses the largest remainder method: each part is first rounded toward zero, then the leftover
+    /// minor units are given one at a time to the parts with the largest remainders, earliest first
+    /// on ties.
+    ///
+    /// Returns an error if `ratios` is empty, contains a negative ratio, or sums to zero.
+    pub fn allocate(&self, ratios: &[Decimal]) -> Result<Vec<Self>, Error> {
+        if ratios.is_empty() {
+            return Err(Error::InvalidArgument(
+                "expected at least one ratio".to_string(),
+            ));
+        }
+        if let Some(ratio) = ratios.iter().find(|ratio| **ratio < Decimal::ZERO) {
+            return Err(Error::InvalidArgument(format!(
+                "expected non-negative ratios, but got {ratio}"
+            )));
+        }
+
+        // Scale the ratios to integers so that the division is exact.
+        let scale = ratios.iter().map(|ratio| ratio.scale()).max().unwrap_or(0);
+        let weights = ratios
+            .iter()
+            .map(|ratio| {
+                10i128
+                    .checked_pow(scale - ratio.scale())
+                    .and_then(|factor| ratio.mantissa().checked_mul(factor))
+            })
+            .collect::<Option<Vec<i128>>>()
+            .ok_or(Error::Overflow)?;
+        let total = weights
+            .iter()
+            .try_fold(0i128, |sum, weight| sum.checked_add(*weight))
+            .ok_or(Error::Overflow)?;
+        if total == 0 {
+            return Err(Error::DivisionByZero);
+        }
+
+        let units = self.minor_units();
+        let mut parts = Vec::with_capacity(weights.len());
+        let mut remainders = Vec::with_capacity(weights.len());
+        for weight in weights {
+            let product = units.abs().checked_mul(weight).ok_or(Error::Overflow)?;
+            parts.push(product / total);
+            remainders.push(product % total);
+        }
+        let leftover = units.abs() - parts.iter().sum::<i128>();
+        let mut order: Vec<usize> = (0..parts.len()).collect();
+        order.sort_by(|a, b| remainders[*b].cmp(&remainders[*a]));
+        for i in order.into_iter().take(leftover as usize) {
+            parts[i] += 1;
+        }
+
+        parts
+            .into_iter()
+            .map(|part| Self::from_minor_units(part * units.signum(), self.currency()))
+            .collect()
+    }
+
     /// Returns true if the `amount` is zero, regardless of currency.
     pub fn is_zero(&self) -> bool {
         self.money.is_zero()
@@ -127,6 +266,22 @@ impl SubAssign for Money {
     }
 }
 
+impl Add<FractionalMoney> for Money {
+    type Output = FractionalMoney;
+
+    fn add(self, rhs: FractionalMoney) -> Self::Output {
+        self.try_add_fractional(&rhs).unwrap()
+    }
+}
+
+impl Sub<FractionalMoney> for Money {
+    type Output = FractionalMoney;
+
+    fn sub(self, rhs: FractionalMoney) -> Self::Output {
+        self.try_subtract_fractional(&rhs).unwrap()
+    }
+}
+
 impl Mul<Decimal> for Money {
     type Output = FractionalMoney;
 
@@ -135,6 +290,65 @@ impl Mul<Decimal> for Money {
     }
 }
 
+impl Mul<Decimal> for &Money {
+    type Output = FractionalMoney;
+
+    fn mul(self, rhs: Decimal) -> Self::Output {
+        self.money * rhs
+    }
+}
+
+/// Multiplying by an integer always results in a valid denomination, so the result is `Money`.
+impl Mul<i64> for Money {
+    type Output = Money;
+
+    fn mul(self, rhs: i64) -> Self::Output {
+        Self {
+            money: self.money * Decimal::from(rhs),
+        }
+    }
+}
+
+impl Mul<i64> for &Money {
+    type Output = Money;
+
+    fn mul(self, rhs: i64) -> Self::Output {
+        *self * rhs
+    }
+}
+
+impl Mul<i32> for Money {
+    type Output = Money;
+
+    fn mul(self, rhs: i32) -> Self::Output {
+        self * i64::from(rhs)
+    }
+}
+
+impl Mul<i32> for &Money {
+    type Output = Money;
+
+    fn mul(self, rhs: i32) -> Self::Output {
+        *self * i64::from(rhs)
+    }
+}
+
+impl Mul<u32> for Money {
+    type Output = Money;
+
+    fn mul(self, rhs: u32) -> Self::Output {
+        self * i64::from(rhs)
+    }
+}
+
+impl Mul<u32> for &Money {
+    type Output = Money;
+
+    fn mul(self, rhs: u32) -> Self::Output {
+        *self * i64::from(rhs)
+    }
+}
+
 impl Div<Decimal> for Money {
     type Output = FractionalMoney;
 
@@ -143,6 +357,14 @@ impl Div<Decimal> for Money {
     }
 }
 
+impl Rem for Money {
+    type Output = Money;
+
+    fn rem(self, rhs: Self) -> Self::Output {
+        self.try_rem(&rhs).unwrap()
+    }
+}
+
 impl Neg for Money {
     type Output = Money;
 
@@ -508,6 +730,213 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn add_fractional() -> Result<()> {
+        let fee = FractionalMoney::new(dec!(0.125), Currency::USD)?;
+        let sum = usd!(1) + fee;
+        expect_eq!(sum.amount(), dec!(1.125));
+        expect_eq!(sum.currency(), Currency::USD);
+
+        let sum = zero!() + fee;
+        expect_eq!(sum, fee);
+        Ok(())
+    }
+
+    #[test]
+    fn subtract_fractional() -> Result<()> {
+        let fee = FractionalMoney::new(dec!(0.125), Currency::USD)?;
+        let difference = usd!(1) - fee;
+        expect_eq!(difference.amount(), dec!(0.875));
+        expect_eq!(difference.currency(), Currency::USD);
+        Ok(())
+    }
+
+    #[test]
+    fn try_add_fractional__mismatched_currency__returns_err() -> Result<()> {
+        let fee = FractionalMoney::new(dec!(0.125), Currency::CAD)?;
+        expect_eq!(
+            expect_err!(usd!(1).try_add_fractional(&fee)),
+            Error::MismatchedCurrency
+        );
+        expect_eq!(
+            expect_err!(usd!(1).try_subtract_fractional(&fee)),
+            Error::MismatchedCurrency
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic]
+    fn add_fractional__mismatched_currencies__panics() {
+        let _ = usd!(1) + FractionalMoney::new(dec!(1), Currency::CAD).unwrap();
+    }
+
+    #[test]
+    fn multiply_by_integer() -> Result<()> {
+        expect_eq!(usd!(1.50) * 3, usd!(4.50));
+        expect_eq!(usd!(1.50) * 3_i64, usd!(4.50));
+        expect_eq!(usd!(1.50) * -2_i64, usd!(-3.00));
+        expect_eq!(usd!(1.50) * 3_u32, usd!(4.50));
+        expect_eq!(zero!() * 3_u32, zero!());
+        expect_eq!((usd!(1.50) * 3_u32).to_string(), "4.50 USD");
+        Ok(())
+    }
+
+    #[test]
+    fn multiply_reference() -> Result<()> {
+        let prices = [usd!(2.23), usd!(0.10)];
+        let doubled: Vec<Money> = prices.iter().map(|p| p * 2_i64).collect();
+        expect_eq!(doubled, vec![usd!(4.46), usd!(0.20)]);
+        let doubled: Vec<Money> = prices.iter().map(|p| p * 2_u32).collect();
+        expect_eq!(doubled, vec![usd!(4.46), usd!(0.20)]);
+        let halved: Vec<Money> = prices.iter().map(|p| (p * dec!(0.5)).round()).collect();
+        expect_eq!(halved, vec![usd!(1.12), usd!(0.05)]);
+        Ok(())
+    }
+
+    #[test]
+    fn minor_units() -> Result<()> {
+        expect_eq!(usd!(13.37).minor_units(), 1337);
+        expect_eq!(usd!(-1).minor_units(), -100);
+        expect_eq!(zero!().minor_units(), 0);
+        // Too large to have 2 decimal places.
+        let max = Money::new(Decimal::MAX, Currency::USD)?;
+        expect_eq!(max.minor_units(), Decimal::MAX.mantissa() * 100);
+        Ok(())
+    }
+
+    #[test]
+    fn from_minor_units() -> Result<()> {
+        expect_eq!(
+            expect_ok!(Money::from_minor_units(1337, Currency::USD)),
+            usd!(13.37)
+        );
+        expect_eq!(
+            expect_ok!(Money::from_minor_units(-5, Currency::CAD)),
+            cad!(-0.05)
+        );
+        expect_eq!(
+            expect_ok!(Money::from_minor_units(0, Currency::ZeroNone)),
+            zero!()
+        );
+        let e = expect_err!(Money::from_minor_units(1, Currency::ZeroNone));
+        expect_eq!(e, Error::ZeroCurrencyWithNonZeroAmount);
+        Ok(())
+    }
+
+    #[test]
+    fn try_ratio() -> Result<()> {
+        expect_eq!(expect_ok!(usd!(5).try_ratio(&usd!(20))), dec!(0.25));
+        expect_eq!(expect_ok!(usd!(-3).try_ratio(&usd!(1.50))), dec!(-2));
+        expect_eq!(expect_ok!(zero!().try_ratio(&usd!(1.50))), dec!(0));
+        Ok(())
+    }
+
+    #[test]
+    fn try_ratio__mismatched_currency__returns_err() -> Result<()> {
+        let e = expect_err!(usd!(1).try_ratio(&cad!(1)));
+        expect_eq!(e, Error::MismatchedCurrency);
+        Ok(())
+    }
+
+    #[test]
+    fn try_ratio__zero_denominator__returns_err() -> Result<()> {
+        expect_eq!(
+            expect_err!(usd!(1).try_ratio(&usd!(0))),
+            Error::DivisionByZero
+        );
+        expect_eq!(
+            expect_err!(usd!(1).try_ratio(&zero!())),
+            Error::DivisionByZero
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn div_rem() -> Result<()> {
+        let (quotient, remainder) = expect_ok!(usd!(10).div_rem(3));
+        expect_eq!(quotient, usd!(3.33));
+        expect_eq!(remainder, usd!(0.01));
+
+        let (quotient, remainder) = expect_ok!(usd!(-10).div_rem(3));
+        expect_eq!(quotient, usd!(-3.33));
+        expect_eq!(remainder, usd!(-0.01));
+
+        let (quotient, remainder) = expect_ok!(cad!(0.05).div_rem(-2));
+        expect_eq!(quotient, cad!(-0.02));
+        expect_eq!(remainder, cad!(0.01));
+        Ok(())
+    }
+
+    #[test]
+    fn div_rem__by_zero__returns_err() -> Result<()> {
+        expect_eq!(expect_err!(usd!(10).div_rem(0)), Error::DivisionByZero);
+        Ok(())
+    }
+
+    #[test]
+    fn rem() -> Result<()> {
+        expect_eq!(usd!(10) % usd!(3), usd!(1));
+        expect_eq!(usd!(10.00) % usd!(0.03), usd!(0.01));
+        expect_eq!(usd!(-10) % usd!(3), usd!(-1));
+        expect_eq!(zero!() % usd!(3), usd!(0));
+        Ok(())
+    }
+
+    #[test]
+    fn try_rem__mismatched_currency__returns_err() -> Result<()> {
+        expect_eq!(
+            expect_err!(usd!(1).try_rem(&cad!(1))),
+            Error::MismatchedCurrency
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn try_rem__by_zero__returns_err() -> Result<()> {
+        expect_eq!(
+            expect_err!(usd!(1).try_rem(&usd!(0))),
+            Error::DivisionByZero
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn allocate() -> Result<()> {
+        let parts = usd!(10).allocate(&[dec!(1), dec!(1), dec!(1)])?;
+        expect_eq!(parts, vec![usd!(3.34), usd!(3.33), usd!(3.33)]);
+
+        // 3.33 split 10:20:3.33 gives 0.999, 1.998 and 0.333 before the leftover cents.
+        let parts = usd!(3.33).allocate(&[dec!(10), dec!(20), dec!(3.33)])?;
+        expect_eq!(parts, vec![usd!(1.00), usd!(2.00), usd!(0.33)]);
+
+        let parts = cad!(-0.05).allocate(&[dec!(0.5), dec!(0), dec!(0.5)])?;
+        expect_eq!(parts, vec![cad!(-0.03), cad!(0), cad!(-0.02)]);
+
+        expect_eq!(
+            zero!().allocate(&[dec!(1), dec!(2)])?,
+            vec![zero!(), zero!()]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn allocate__invalid_ratios__returns_err() -> Result<()> {
+        expect_err!(usd!(1).allocate(&[]));
+        expect_err!(usd!(1).allocate(&[dec!(1), dec!(-1)]));
+        expect_eq!(
+            expect_err!(usd!(1).allocate(&[dec!(0), dec!(0)])),
+            Error::DivisionByZero
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic]
+    fn rem__mismatched_currencies__panics() {
+        let _ = usd!(1) % cad!(1);
+    }
+
     #[test]
     fn neg() -> Result<()> {
         let a = usd!(1);
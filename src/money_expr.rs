@@ -0,0 +1,334 @@
+use crate::currency;
+use crate::currency::Currency;
+use crate::error::Error;
+use crate::fractional_money::FractionalMoney;
+use crate::money::Money;
+use crate::rational_money::RationalMoney;
+use crate::rounding::RoundingMode;
+use rust_decimal::Decimal;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Node {
+    Value(FractionalMoney),
+    Add(Box<Node>, Box<Node>),
+    Subtract(Box<Node>, Box<Node>),
+    Multiply(Box<Node>, Decimal),
+    Divide(Box<Node>, Decimal),
+    Negate(Box<Node>),
+}
+
+/// A deferred monetary calculation, built up with the usual operators, e.g.,
+/// `(MoneyExpr::new(price) * rate + fee) / n`.
+///
+/// Currencies are checked as the expression is built, but nothing is computed until `evaluate()`
+/// is called, at which point the whole expression is computed exactly (see `RationalMoney`) and
+/// rounded once.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MoneyExpr {
+    node: Node,
+    currency: Currency,
+}
+
+impl MoneyExpr {
+    /// Creates an expression consisting of a single value.
+    pub fn new(value: impl Into<FractionalMoney>) -> Self {
+        let value = value.into();
+        Self {
+            node: Node::Value(value),
+            currency: value.currency(),
+        }
+    }
+
+    /// The currency that the expression will evaluate to.
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    /// Attempts to add another expression to this one. Returns an error if the currencies do not
+    /// match.
+    pub fn try_add(&self, rhs: &Self) -> Result<Self, Error> {
+        self.clone().combine(rhs.clone(), Node::Add)
+    }
+
+    /// Attempts to subtract another expression from this one. Returns an error if the currencies
+    /// do not match.
+    pub fn try_subtract(&self, rhs: &Self) -> Result<Self, Error> {
+        self.clone().combine(rhs.clone(), Node::Subtract)
+    }
+
+    /// Evaluates the expression exactly, without any rounding. Returns an error if the expression
+    /// divides by zero.
+    pub fn evaluate_exact(&self) -> Result<RationalMoney, Error> {
+        evaluate(&self.node)
+    }
+
+    /// Evaluates the expression exactly and rounds the result once, using the given rounding
+    /// method. Returns an error if the expression divides by zero or if the result is out of
+    /// range.
+    pub fn evaluate(&self, mode: RoundingMode) -> Result<Money, Error> {
+        self.evaluate_exact()?.round_with(mode)
+    }
+
+    /// Joins two expressions with a binary node, moving rather than copying their nodes.
+    fn combine(self, rhs: Self, node: fn(Box<Node>, Box<Node>) -> Node) -> Result<Self, Error> {
+        Ok(Self {
+            currency: currency::combine_currency(self.currency, rhs.currency)?,
+            node: node(Box::new(self.node), Box::new(rhs.node)),
+        })
+    }
+}
+
+fn evaluate(node: &Node) -> Result<RationalMoney, Error> {
+    Ok(match node {
+        Node::Value(value) => RationalMoney::from(*value),
+        Node::Add(lhs, rhs) => evaluate(lhs)?.try_add(&evaluate(rhs)?)?,
+        Node::Subtract(lhs, rhs) => evaluate(lhs)?.try_subtract(&evaluate(rhs)?)?,
+        Node::Multiply(lhs, scalar) => evaluate(lhs)? * *scalar,
+        Node::Divide(lhs, scalar) => {
+            if scalar.is_zero() {
+                return Err(Error::DivisionByZero);
+            }
+            evaluate(lhs)? / *scalar
+        }
+        Node::Negate(operand) => -evaluate(operand)?,
+    })
+}
+
+/// The binding strength of each kind of node, used to decide where parentheses are needed.
+fn precedence(node: &Node) -> u8 {
+    match node {
+        Node::Add(..) | Node::Subtract(..) => 1,
+        Node::Multiply(..) | Node::Divide(..) => 2,
+        Node::Negate(..) => 3,
+        Node::Value(value) if value.is_negative() => 3,
+        Node::Value(_) => 4,
+    }
+}
+
+fn fmt_node(node: &Node, f: &mut Formatter<'_>, min_precedence: u8) -> std::fmt::Result {
+    let parenthesize = precedence(node) < min_precedence;
+    if parenthesize {
+        write!(f, "(")?;
+    }
+    match node {
+        Node::Value(value) => write!(f, "{value}")?,
+        Node::Add(lhs, rhs) => {
+            fmt_node(lhs, f, 1)?;
+            write!(f, " + ")?;
+            fmt_node(rhs, f, 2)?;
+        }
+        Node::Subtract(lhs, rhs) => {
+            fmt_node(lhs, f, 1)?;
+            write!(f, " - ")?;
+            fmt_node(rhs, f, 2)?;
+        }
+        Node::Multiply(lhs, scalar) => {
+            fmt_node(lhs, f, 2)?;
+            write!(f, " * ")?;
+            fmt_scalar(scalar, f)?;
+        }
+        Node::Divide(lhs, scalar) => {
+            fmt_node(lhs, f, 2)?;
+            write!(f, " / ")?;
+            fmt_scalar(scalar, f)?;
+        }
+        Node::Negate(operand) => {
+            write!(f, "-")?;
+            fmt_node(operand, f, 4)?;
+        }
+    }
+    if parenthesize {
+        write!(f, ")")?;
+    }
+    Ok(())
+}
+
+fn fmt_scalar(scalar: &Decimal, f: &mut Formatter<'_>) -> std::fmt::Result {
+    if scalar.is_sign_negative() {
+        write!(f, "({scalar})")
+    } else {
+        write!(f, "{scalar}")
+    }
+}
+
+/// Displays the expression as a formula, e.g., `(13.37 USD * 1.05 + 2.00 USD) / 3`.
+impl Display for MoneyExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        fmt_node(&self.node, f, 0)
+    }
+}
+
+impl From<FractionalMoney> for MoneyExpr {
+    fn from(value: FractionalMoney) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<Money> for MoneyExpr {
+    fn from(value: Money) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: Into<MoneyExpr>> Add<T> for MoneyExpr {
+    type Output = MoneyExpr;
+
+    fn add(self, rhs: T) -> Self::Output {
+        self.combine(rhs.into(), Node::Add).unwrap()
+    }
+}
+
+impl<T: Into<MoneyExpr>> Sub<T> for MoneyExpr {
+    type Output = MoneyExpr;
+
+    fn sub(self, rhs: T) -> Self::Output {
+        self.combine(rhs.into(), Node::Subtract).unwrap()
+    }
+}
+
+impl Mul<Decimal> for MoneyExpr {
+    type Output = MoneyExpr;
+
+    fn mul(self, scalar: Decimal) -> Self::Output {
+        Self {
+            node: Node::Multiply(Box::new(self.node), scalar),
+            currency: self.currency,
+        }
+    }
+}
+
+/// Division by zero is only reported when the expression is evaluated.
+impl Div<Decimal> for MoneyExpr {
+    type Output = MoneyExpr;
+
+    fn div(self, scalar: Decimal) -> Self::Output {
+        Self {
+            node: Node::Divide(Box::new(self.node), scalar),
+            currency: self.currency,
+        }
+    }
+}
+
+impl Neg for MoneyExpr {
+    type Output = MoneyExpr;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            node: Node::Negate(Box::new(self.node)),
+            currency: self.currency,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::{cad, usd};
+    use anyhow::Result;
+    use expecting::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn evaluate__rounds_once_at_end() -> Result<()> {
+        let expr = MoneyExpr::new(usd!(1)) * dec!(0.99499) * dec!(0.98);
+        expect_eq!(
+            expect_ok!(expr.evaluate(RoundingMode::MidpointNearestEven)),
+            usd!(0.98)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn evaluate__is_exact() -> Result<()> {
+        let expr = MoneyExpr::new(usd!(1)) / dec!(3) * dec!(3);
+        expect_eq!(
+            expect_ok!(expr.evaluate_exact()),
+            RationalMoney::from(usd!(1))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn builder_methods() -> Result<()> {
+        let expr = MoneyExpr::new(usd!(13.37))
+            .mul(dec!(1.05))
+            .add(usd!(2))
+            .div(dec!(3));
+        // (14.0385 + 2) / 3 = 5.34616...
+        expect_eq!(
+            expect_ok!(expr.evaluate(RoundingMode::MidpointNearestEven)),
+            usd!(5.35)
+        );
+        expect_eq!(expr.currency(), Currency::USD);
+        Ok(())
+    }
+
+    #[test]
+    fn evaluate__rounding_mode() -> Result<()> {
+        let expr = MoneyExpr::new(usd!(0.01)) / dec!(2);
+        expect_eq!(expr.evaluate(RoundingMode::MidpointNearestEven)?, usd!(0));
+        expect_eq!(
+            expr.evaluate(RoundingMode::MidpointAwayFromZero)?,
+            usd!(0.01)
+        );
+        expect_eq!((-expr).evaluate(RoundingMode::ToPositiveInfinity)?, usd!(0));
+        Ok(())
+    }
+
+    #[test]
+    fn evaluate__divide_by_zero__returns_err() -> Result<()> {
+        let expr = MoneyExpr::new(usd!(1)) / dec!(0);
+        expect_eq!(
+            expect_err!(expr.evaluate(RoundingMode::default())),
+            Error::DivisionByZero
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn try_add__mismatched_currency__returns_err() -> Result<()> {
+        let expr = MoneyExpr::new(usd!(1)) * dec!(2);
+        expect_eq!(
+            expect_err!(expr.try_add(&cad!(1).into())),
+            Error::MismatchedCurrency
+        );
+        expect_eq!(
+            expect_err!(expr.try_subtract(&cad!(1).into())),
+            Error::MismatchedCurrency
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic]
+    fn add__mismatched_currencies__panics() {
+        let _ = MoneyExpr::new(usd!(1)) + cad!(1);
+    }
+
+    #[test]
+    fn zero_currency_takes_other_currency() -> Result<()> {
+        let expr = MoneyExpr::new(Money::zero()) + cad!(1);
+        expect_eq!(expr.currency(), Currency::CAD);
+        expect_eq!(expr.evaluate(RoundingMode::default())?, cad!(1));
+        Ok(())
+    }
+
+    #[test]
+    fn display() -> Result<()> {
+        let expr = (MoneyExpr::new(usd!(13.37)) * dec!(1.05) + usd!(2)) / dec!(3);
+        expect_eq!(expr.to_string(), "(13.37 USD * 1.05 + 2.00 USD) / 3");
+
+        let expr = MoneyExpr::new(usd!(10)) - (MoneyExpr::new(usd!(3)) - usd!(1));
+        expect_eq!(expr.to_string(), "10.00 USD - (3.00 USD - 1.00 USD)");
+
+        let expr = -(MoneyExpr::new(usd!(-1)) + usd!(2)) * dec!(-2);
+        expect_eq!(expr.to_string(), "-(-1.00 USD + 2.00 USD) * (-2)");
+
+        let expr = MoneyExpr::new(usd!(-1)) * dec!(2);
+        expect_eq!(expr.to_string(), "-1.00 USD * 2");
+        Ok(())
+    }
+}
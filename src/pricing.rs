@@ -0,0 +1,540 @@
+use crate::currency;
+use crate::currency::Currency;
+use crate::error::Error;
+use crate::fractional_money::FractionalMoney;
+use crate::money::Money;
+use crate::rounding::RoundingMode;
+use rust_decimal::Decimal;
+
+/// A line of an order: a quantity of a product at a unit price.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineItem {
+    pub sku: String,
+    pub unit_price: Money,
+    pub quantity: u32,
+}
+
+impl LineItem {
+    pub fn new(sku: impl Into<String>, unit_price: Money, quantity: u32) -> Self {
+        Self {
+            sku: sku.into(),
+            unit_price,
+            quantity,
+        }
+    }
+
+    /// The unit price times the quantity.
+    pub fn subtotal(&self) -> Result<Money, Error> {
+        let units = self
+            .unit_price
+            .minor_units()
+            .checked_mul(i128::from(self.quantity))
+            .ok_or(Error::Overflow)?;
+        Money::from_minor_units(units, self.unit_price.currency())
+    }
+}
+
+/// How a `Promotion` reduces the price of the lines it applies to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Discount {
+    /// A fraction off the lines' total, e.g., `Discount::PercentOff(dec!(0.1))` for 10% off.
+    PercentOff(Decimal),
+    /// An amount off the lines' total, up to the total.
+    FixedOff(Money),
+    /// For every `buy` units of a line, the next `get` units are free, e.g., buying 5 units with
+    /// `buy: 1, get: 2` gets 3 free.
+    BuyXGetY { buy: u32, get: u32 },
+    /// An amount off the lines' total if the total is at least `minimum`.
+    Threshold { minimum: Money, off: Money },
+}
+
+/// A named discount, optionally limited to certain products.
+///
+/// Promotions apply in ascending order of priority (and then in the order given), each to the
+/// amounts left by the previous ones, so the order affects the result: 10% off and then 5.00 off
+/// 100.00 is 85.00, but 5.00 off and then 10% off is 85.50. An exclusive promotion does not
+/// combine with any other promotion; see `Pricing::price()`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Promotion {
+    name: String,
+    discount: Discount,
+    skus: Option<Vec<String>>,
+    priority: i32,
+    exclusive: bool,
+}
+
+impl Promotion {
+    /// Creates a non-exclusive promotion on every line, with priority zero.
+    pub fn new(name: impl Into<String>, discount: Discount) -> Self {
+        Self {
+            name: name.into(),
+            discount,
+            skus: None,
+            priority: 0,
+            exclusive: false,
+        }
+    }
+
+    /// Limits the promotion to lines with the given SKUs.
+    pub fn for_skus<S: Into<String>>(mut self, skus: impl IntoIterator<Item = S>) -> Self {
+        self.skus = Some(skus.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn exclusive(mut self) -> Self {
+        self.exclusive = true;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn discount(&self) -> Discount {
+        self.discount
+    }
+
+    pub fn is_exclusive(&self) -> bool {
+        self.exclusive
+    }
+
+    fn applies_to(&self, line: &LineItem) -> bool {
+        match &self.skus {
+            Some(skus) => skus.contains(&line.sku),
+            None => true,
+        }
+    }
+
+    fn currency(&self) -> Currency {
+        match self.discount {
+            Discount::PercentOff(_) | Discount::BuyXGetY { .. } => Currency::ZeroNone,
+            Discount::FixedOff(off) => off.currency(),
+            Discount::Threshold { minimum, off } => {
+                currency::combine_currency(minimum.currency(), off.currency())
+                    .unwrap_or(Currency::ZeroNone)
+            }
+        }
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        let invalid = |details: String| {
+            Err(Error::InvalidArgument(format!(
+                "promotion {}: {details}",
+                self.name
+            )))
+        };
+        match self.discount {
+            Discount::PercentOff(rate) if rate < Decimal::ZERO || rate > Decimal::ONE => {
+                invalid(format!("expected a percentage from 0 to 1, but got {rate}"))
+            }
+            Discount::FixedOff(off) | Discount::Threshold { off, .. } if off.is_negative() => {
+                invalid(format!("expected a non-negative amount, but got {off}"))
+            }
+            Discount::BuyXGetY { buy, get } if buy == 0 || get == 0 => invalid(format!(
+                "expected to buy and get at least one unit, but got buy {buy} get {get}"
+            )),
+            Discount::Threshold { minimum, off } => {
+                currency::combine_currency(minimum.currency(), off.currency())?;
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// An amount taken off a line by a promotion.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineAdjustment {
+    pub promotion: String,
+    pub amount: Money,
+}
+
+/// A line of a `PricedOrder`, with the promotions applied to it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PricedLine {
+    pub item: LineItem,
+    pub subtotal: Money,
+    /// The amounts taken off by each promotion, in the order they applied. Promotions which took
+    /// nothing off the line are omitted.
+    pub adjustments: Vec<LineAdjustment>,
+    pub discount: Money,
+    pub total: Money,
+}
+
+/// An order with its promotions applied.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PricedOrder {
+    pub lines: Vec<PricedLine>,
+    pub subtotal: Money,
+    pub discount: Money,
+    pub total: Money,
+}
+
+impl PricedOrder {
+    /// The total amount taken off by the named promotion, across all lines.
+    pub fn discount_for(&self, promotion: &str) -> Money {
+        self.lines
+            .iter()
+            .flat_map(|line| &line.adjustments)
+            .filter(|adjustment| adjustment.promotion == promotion)
+            .map(|adjustment| adjustment.amount)
+            .sum()
+    }
+
+    /// The names of the promotions which took something off, in the order they applied.
+    pub fn applied(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = vec![];
+        for adjustment in self.lines.iter().flat_map(|line| &line.adjustments) {
+            if !names.contains(&adjustment.promotion.as_str()) {
+                names.push(&adjustment.promotion);
+            }
+        }
+        names
+    }
+}
+
+/// Applies promotions to orders.
+///
+/// Discounts on the total of several lines (percent off, fixed off and threshold discounts) are
+/// rounded once and then prorated back to the lines in proportion to their amounts with
+/// `Money::allocate()`, so that the lines' discounts always add up to the order's.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Pricing {
+    promotions: Vec<Promotion>,
+    rounding: RoundingMode,
+}
+
+impl Pricing {
+    /// Creates pricing with the given promotions, using the default rounding method.
+    pub fn new(promotions: Vec<Promotion>) -> Self {
+        Self {
+            promotions,
+            rounding: RoundingMode::default(),
+        }
+    }
+
+    /// Sets the rounding method used for percentage and buy-X-get-Y discounts.
+    pub fn with_rounding(mut self, rounding: RoundingMode) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    pub fn promotions(&self) -> &[Promotion] {
+        &self.promotions
+    }
+
+    /// Prices an order. The non-exclusive promotions are stacked, and each exclusive promotion is
+    /// tried on its own; whichever of these gives the largest discount is used, preferring the
+    /// stacked promotions on a tie.
+    ///
+    /// Returns an error if a promotion is invalid or the currencies do not match.
+    pub fn price(&self, items: &[LineItem]) -> Result<PricedOrder, Error> {
+        let mut currency = Currency::ZeroNone;
+        for item in items {
+            currency = currency::combine_currency(currency, item.unit_price.currency())?;
+        }
+        for promotion in &self.promotions {
+            promotion.validate()?;
+            currency = currency::combine_currency(currency, promotion.currency())?;
+        }
+        let subtotals = items
+            .iter()
+            .map(LineItem::subtotal)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stacked: Vec<&Promotion> =
+            self.promotions.iter().filter(|p| !p.exclusive).collect();
+        stacked.sort_by_key(|p| p.priority);
+        let mut candidates = vec![stacked];
+        candidates.extend(
+            self.promotions
+                .iter()
+                .filter(|p| p.exclusive)
+                .map(|p| vec![p]),
+        );
+
+        let mut best: Option<(Money, Vec<Vec<LineAdjustment>>)> = None;
+        for candidate in candidates {
+            let adjustments = self.apply(&candidate, items, &subtotals)?;
+            let discount: Money = adjustments.iter().flatten().map(|a| a.amount).sum();
+            if best.as_ref().is_none_or(|(most, _)| discount > *most) {
+                best = Some((discount, adjustments));
+            }
+        }
+        let adjustments = best.map(|(_, adjustments)| adjustments).unwrap_or_default();
+
+        let zero = Money::new(Decimal::ZERO, currency)?;
+        let mut lines = vec![];
+        for ((item, subtotal), adjustments) in items.iter().zip(subtotals).zip(adjustments) {
+            let discount = adjustments
+                .iter()
+                .map(|a| a.amount)
+                .fold(zero, |a, b| a + b);
+            lines.push(PricedLine {
+                item: item.clone(),
+                subtotal,
+                total: subtotal.try_subtract(&discount)?,
+                adjustments,
+                discount,
+            });
+        }
+        let subtotal = lines.iter().map(|l| l.subtotal).fold(zero, |a, b| a + b);
+        let discount = lines.iter().map(|l| l.discount).fold(zero, |a, b| a + b);
+        Ok(PricedOrder {
+            lines,
+            subtotal,
+            total: subtotal.try_subtract(&discount)?,
+            discount,
+        })
+    }
+
+    /// Applies promotions in order, returning each line's adjustments.
+    fn apply(
+        &self,
+        promotions: &[&Promotion],
+        items: &[LineItem],
+        subtotals: &[Money],
+    ) -> Result<Vec<Vec<LineAdjustment>>, Error> {
+        let mut amounts = subtotals.to_vec();
+        let mut adjustments = vec![vec![]; items.len()];
+
+        for promotion in promotions {
+            let lines: Vec<usize> = (0..items.len())
+                .filter(|i| promotion.applies_to(&items[*i]))
+                .collect();
+            let current: Vec<Money> = lines.iter().map(|i| amounts[*i]).collect();
+            let total: Money = current.iter().copied().sum();
+
+            let discounts = match promotion.discount {
+                Discount::PercentOff(rate) => {
+                    let off = total.amount().checked_mul(rate).ok_or(Error::Overflow)?;
+                    let off =
+                        FractionalMoney::new(off, total.currency())?.round_with(self.rounding);
+                    prorate(off, &current)?
+                }
+                Discount::FixedOff(off) => prorate(off.min(total), &current)?,
+                Discount::Threshold { minimum, off } if total >= minimum => {
+                    prorate(off.min(total), &current)?
+                }
+                Discount::Threshold { .. } => vec![],
+                Discount::BuyXGetY { buy, get } => {
+                    let mut discounts = vec![];
+                    for (i, amount) in lines.iter().zip(&current) {
+                        let quantity = items[*i].quantity;
+                        let free = quantity / (buy + get) * get
+                            + (quantity % (buy + get)).saturating_sub(buy);
+                        let off = amount
+                            .amount()
+                            .checked_mul(Decimal::from(free))
+                            .and_then(|off| off.checked_div(Decimal::from(quantity.max(1))))
+                            .ok_or(Error::Overflow)?;
+                        let off =
+                            FractionalMoney::new(off, amount.currency())?.round_with(self.rounding);
+                        discounts.push(off.min(*amount));
+                    }
+                    discounts
+                }
+            };
+
+            for (i, discount) in lines.iter().zip(discounts) {
+                if discount.is_zero() {
+                    continue;
+                }
+                amounts[*i] = amounts[*i].try_subtract(&discount)?;
+                adjustments[*i].push(LineAdjustment {
+                    promotion: promotion.name.clone(),
+                    amount: discount,
+                });
+            }
+        }
+        Ok(adjustments)
+    }
+}
+
+/// Prorates a discount to lines in proportion to their amounts.
+fn prorate(discount: Money, amounts: &[Money]) -> Result<Vec<Money>, Error> {
+    if discount.is_zero() {
+        return Ok(vec![]);
+    }
+    let ratios: Vec<Decimal> = amounts.iter().map(|amount| amount.amount()).collect();
+    discount.allocate(&ratios)
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::{cad, usd};
+    use anyhow::Result;
+    use expecting::*;
+    use rust_decimal_macros::dec;
+
+    fn line_discounts(order: &PricedOrder) -> Vec<Money> {
+        order.lines.iter().map(|line| line.discount).collect()
+    }
+
+    #[test]
+    fn price__no_promotions() -> Result<()> {
+        let order = Pricing::default().price(&[
+            LineItem::new("A", usd!(2.50), 3),
+            LineItem::new("B", usd!(1), 1),
+        ])?;
+        expect_eq!(order.subtotal, usd!(8.50));
+        expect_eq!(order.discount, usd!(0));
+        expect_eq!(order.total, usd!(8.50));
+        expect_empty!(order.applied());
+
+        let order = Pricing::default().price(&[])?;
+        expect_eq!(order.total, Money::zero());
+        Ok(())
+    }
+
+    #[test]
+    fn price__percent_off__prorated_exactly() -> Result<()> {
+        let pricing = Pricing::new(vec![Promotion::new(
+            "10% off",
+            Discount::PercentOff(dec!(0.1)),
+        )]);
+        let order = pricing.price(&[
+            LineItem::new("A", usd!(10), 1),
+            LineItem::new("B", usd!(20), 1),
+            LineItem::new("C", usd!(3.33), 1),
+        ])?;
+        // 3.333 rounds to 3.33, which is prorated as 0.999, 1.998 and 0.333.
+        expect_eq!(order.discount, usd!(3.33));
+        expect_eq!(line_discounts(&order), vec![usd!(1), usd!(2), usd!(0.33)]);
+        expect_eq!(order.total, usd!(30));
+        expect_eq!(
+            order.lines[0].adjustments.clone(),
+            vec![LineAdjustment {
+                promotion: "10% off".to_string(),
+                amount: usd!(1),
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn price__stacking_order() -> Result<()> {
+        let percent = Promotion::new("10% off", Discount::PercentOff(dec!(0.1)));
+        let fixed = Promotion::new("5 off", Discount::FixedOff(usd!(5)));
+        let items = [LineItem::new("A", usd!(100), 1)];
+
+        let order = Pricing::new(vec![percent.clone(), fixed.clone()]).price(&items)?;
+        expect_eq!(order.total, usd!(85));
+        expect_eq!(order.applied(), vec!["10% off", "5 off"]);
+
+        let order =
+            Pricing::new(vec![percent.clone(), fixed.clone().with_priority(-1)]).price(&items)?;
+        expect_eq!(order.total, usd!(85.50));
+        expect_eq!(order.applied(), vec!["5 off", "10% off"]);
+        expect_eq!(order.discount_for("10% off"), usd!(9.50));
+        Ok(())
+    }
+
+    #[test]
+    fn price__fixed_off__limited_to_total() -> Result<()> {
+        let pricing = Pricing::new(vec![Promotion::new("20 off", Discount::FixedOff(cad!(20)))]);
+        let order = pricing.price(&[
+            LineItem::new("A", cad!(5), 1),
+            LineItem::new("B", cad!(10), 1),
+        ])?;
+        expect_eq!(order.total, cad!(0));
+        expect_eq!(line_discounts(&order), vec![cad!(5), cad!(10)]);
+        Ok(())
+    }
+
+    #[test]
+    fn price__buy_x_get_y() -> Result<()> {
+        let pricing = Pricing::new(vec![Promotion::new(
+            "buy 2 get 1",
+            Discount::BuyXGetY { buy: 2, get: 1 },
+        )
+        .for_skus(["A"])]);
+        let order = pricing.price(&[
+            LineItem::new("A", usd!(3), 7),
+            LineItem::new("B", usd!(3), 7),
+        ])?;
+        expect_eq!(line_discounts(&order), vec![usd!(6), usd!(0)]);
+        expect!(order.lines[1].adjustments.is_empty());
+
+        let pricing = Pricing::new(vec![Promotion::new(
+            "buy 1 get 2",
+            Discount::BuyXGetY { buy: 1, get: 2 },
+        )]);
+        // 5 units is one free pair plus one more free unit in the incomplete group.
+        let order = pricing.price(&[LineItem::new("A", usd!(1), 5)])?;
+        expect_eq!(order.discount, usd!(3));
+        Ok(())
+    }
+
+    #[test]
+    fn price__threshold() -> Result<()> {
+        let pricing = Pricing::new(vec![Promotion::new(
+            "spend 50 save 5",
+            Discount::Threshold {
+                minimum: usd!(50),
+                off: usd!(5),
+            },
+        )]);
+        let order = pricing.price(&[LineItem::new("A", usd!(49.99), 1)])?;
+        expect_eq!(order.discount, usd!(0));
+        let order = pricing.price(&[
+            LineItem::new("A", usd!(25), 1),
+            LineItem::new("B", usd!(12.50), 2),
+        ])?;
+        expect_eq!(line_discounts(&order), vec![usd!(2.50), usd!(2.50)]);
+        Ok(())
+    }
+
+    #[test]
+    fn price__exclusive() -> Result<()> {
+        let pricing = Pricing::new(vec![
+            Promotion::new("10% off", Discount::PercentOff(dec!(0.1))),
+            Promotion::new("5 off", Discount::FixedOff(usd!(5))),
+            Promotion::new("20% off", Discount::PercentOff(dec!(0.2))).exclusive(),
+        ]);
+        let order = pricing.price(&[LineItem::new("A", usd!(100), 1)])?;
+        expect_eq!(order.applied(), vec!["20% off"]);
+        expect_eq!(order.total, usd!(80));
+
+        let order = pricing.price(&[LineItem::new("A", usd!(20), 1)])?;
+        expect_eq!(order.applied(), vec!["10% off", "5 off"]);
+        expect_eq!(order.total, usd!(13));
+        Ok(())
+    }
+
+    #[test]
+    fn price__invalid__returns_err() -> Result<()> {
+        let items = [LineItem::new("A", usd!(1), 1)];
+        expect_err!(
+            Pricing::new(vec![Promotion::new("bad", Discount::PercentOff(dec!(1.5)))])
+                .price(&items)
+        );
+        expect_err!(Pricing::new(vec![Promotion::new(
+            "bad",
+            Discount::BuyXGetY { buy: 0, get: 1 }
+        )])
+        .price(&items));
+        expect_eq!(
+            expect_err!(
+                Pricing::new(vec![Promotion::new("bad", Discount::FixedOff(cad!(1)))])
+                    .price(&items)
+            ),
+            Error::MismatchedCurrency
+        );
+        expect_eq!(
+            expect_err!(Pricing::default().price(&[
+                LineItem::new("A", usd!(1), 1),
+                LineItem::new("B", cad!(1), 1),
+            ])),
+            Error::MismatchedCurrency
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,318 @@
+//! Proration of subscription prices when a plan changes part way through a billing period.
+
+use crate::currency;
+use crate::error::Error;
+use crate::fractional_money::FractionalMoney;
+use crate::money::Money;
+use crate::rounding::RoundingMode;
+use chrono::{Datelike, Months, NaiveDateTime};
+use rust_decimal::Decimal;
+
+/// How the unused part of a billing period is measured.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ProrationMethod {
+    /// Whole calendar days. The day of the change counts as unused.
+    #[default]
+    Daily,
+    /// Seconds, i.e., the exact time remaining.
+    PerSecond,
+    /// Whole months remaining after the change, e.g., for an annual plan billed by the month. The
+    /// billing period must be a whole number of months, and a partial month counts as used.
+    WholeMonths,
+}
+
+impl ProrationMethod {
+    fn unit(self) -> &'static str {
+        match self {
+            Self::Daily => "days",
+            Self::PerSecond => "seconds",
+            Self::WholeMonths => "months",
+        }
+    }
+}
+
+/// The result of prorating a plan change.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProratedChange {
+    /// The unused part of the old price, to be credited.
+    pub credit: Money,
+    /// The part of the new price for the rest of the period, to be charged.
+    pub charge: Money,
+    /// `charge - credit`, which is negative for a downgrade.
+    pub net: Money,
+    /// The number of units (days, seconds or months) remaining in the period after the change.
+    pub remaining: i64,
+    /// The number of units in the whole period.
+    pub length: i64,
+    /// `remaining / length`. The prices are multiplied by `remaining` and divided by `length`
+    /// before rounding, so this value is only informational.
+    pub fraction: Decimal,
+    /// A description of the fraction and how it was applied, e.g., "17 of 31 days remaining:
+    /// credit 17/31 of 31.00 USD, charge 17/31 of 62.00 USD".
+    pub explanation: String,
+}
+
+/// Prorates the prices of subscription plans when the plan changes part way through a billing
+/// period: the unused part of the old price is credited and the rest of the period is charged at
+/// the new price.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Proration {
+    method: ProrationMethod,
+    rounding: RoundingMode,
+}
+
+impl Proration {
+    /// Creates a proration using the default rounding method.
+    pub fn new(method: ProrationMethod) -> Self {
+        Self {
+            method,
+            rounding: RoundingMode::default(),
+        }
+    }
+
+    pub fn with_rounding(mut self, rounding: RoundingMode) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    pub fn method(&self) -> ProrationMethod {
+        self.method
+    }
+
+    /// Prorates a change at `at` from a plan priced at `old_price` per period to one priced at
+    /// `new_price`, in the billing period from `start` (inclusive) to `end` (exclusive).
+    ///
+    /// Returns an error if the currencies do not match, the period is empty, the change is outside
+    /// the period, or the period is not a whole number of months when prorating by month.
+    pub fn change_plan(
+        &self,
+        old_price: Money,
+        new_price: Money,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+        at: NaiveDateTime,
+    ) -> Result<ProratedChange, Error> {
+        currency::combine_currency(old_price.currency(), new_price.currency())?;
+        if end <= start {
+            return Err(Error::InvalidArgument(format!(
+                "the billing period ends at {end}, which is not after its start at {start}"
+            )));
+        }
+        if at < start || at > end {
+            return Err(Error::InvalidArgument(format!(
+                "the change at {at} is outside the billing period from {start} to {end}"
+            )));
+        }
+
+        let (remaining, length) = self.units(start, end, at)?;
+        let credit = self.prorate(old_price, remaining, length)?;
+        let charge = self.prorate(new_price, remaining, length)?;
+        let fraction = Decimal::from(remaining)
+            .checked_div(Decimal::from(length))
+            .ok_or(Error::Overflow)?;
+        let unit = self.method.unit();
+        Ok(ProratedChange {
+            credit,
+            charge,
+            net: charge.try_subtract(&credit)?,
+            remaining,
+            length,
+            fraction,
+            explanation: format!(
+                "{remaining} of {length} {unit} remaining: credit {remaining}/{length} of \
+                 {old_price}, charge {remaining}/{length} of {new_price}"
+            ),
+        })
+    }
+
+    /// The units remaining after `at`, and in the whole period.
+    fn units(
+        &self,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+        at: NaiveDateTime,
+    ) -> Result<(i64, i64), Error> {
+        match self.method {
+            ProrationMethod::Daily => Ok((
+                (end.date() - at.date()).num_days(),
+                (end.date() - start.date()).num_days(),
+            )),
+            ProrationMethod::PerSecond => {
+                Ok(((end - at).num_seconds(), (end - start).num_seconds()))
+            }
+            ProrationMethod::WholeMonths => {
+                let length = whole_months(start, end);
+                if add_months(start, length) != Some(end) {
+                    return Err(Error::InvalidArgument(format!(
+                        "the billing period from {start} to {end} is not a whole number of months"
+                    )));
+                }
+                Ok((whole_months(at, end), length))
+            }
+        }
+    }
+
+    /// `price * remaining / length`, rounded.
+    fn prorate(&self, price: Money, remaining: i64, length: i64) -> Result<Money, Error> {
+        if length == 0 {
+            return Err(Error::InvalidArgument(format!(
+                "the billing period has no whole {}",
+                self.method.unit()
+            )));
+        }
+        let amount = price
+            .amount()
+            .checked_mul(Decimal::from(remaining))
+            .and_then(|amount| amount.checked_div(Decimal::from(length)))
+            .ok_or(Error::Overflow)?;
+        Ok(FractionalMoney::new(amount, price.currency())?.round_with(self.rounding))
+    }
+}
+
+/// The number of whole months from `from` to `to`.
+fn whole_months(from: NaiveDateTime, to: NaiveDateTime) -> i64 {
+    let mut months =
+        i64::from(to.year() - from.year()) * 12 + i64::from(to.month()) - i64::from(from.month());
+    while months > 0 && add_months(from, months).is_none_or(|date| date > to) {
+        months -= 1;
+    }
+    months.max(0)
+}
+
+fn add_months(date: NaiveDateTime, months: i64) -> Option<NaiveDateTime> {
+    date.checked_add_months(Months::new(u32::try_from(months).ok()?))
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::{cad, usd};
+    use anyhow::Result;
+    use chrono::NaiveDate;
+    use expecting::*;
+    use rust_decimal_macros::dec;
+
+    fn at(year: i32, month: u32, day: u32, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn change_plan__daily() -> Result<()> {
+        let change = Proration::new(ProrationMethod::Daily).change_plan(
+            usd!(31),
+            usd!(62),
+            at(2024, 1, 1, 0),
+            at(2024, 2, 1, 0),
+            at(2024, 1, 15, 10),
+        )?;
+        expect_eq!(change.credit, usd!(17));
+        expect_eq!(change.charge, usd!(34));
+        expect_eq!(change.net, usd!(17));
+        expect_eq!((change.remaining, change.length), (17, 31));
+        expect_eq!(
+            change.explanation,
+            "17 of 31 days remaining: credit 17/31 of 31.00 USD, charge 17/31 of 62.00 USD"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn change_plan__per_second() -> Result<()> {
+        let proration = Proration::new(ProrationMethod::PerSecond);
+        let change = proration.change_plan(
+            usd!(10),
+            usd!(20.01),
+            at(2024, 4, 1, 0),
+            at(2024, 5, 1, 0),
+            at(2024, 4, 16, 0),
+        )?;
+        expect_eq!(change.fraction, dec!(0.5));
+        expect_eq!(change.credit, usd!(5));
+        // 10.005 rounds to even.
+        expect_eq!(change.charge, usd!(10.00));
+
+        let change = proration
+            .with_rounding(RoundingMode::MidpointAwayFromZero)
+            .change_plan(
+                usd!(10),
+                usd!(20.01),
+                at(2024, 4, 1, 0),
+                at(2024, 5, 1, 0),
+                at(2024, 4, 16, 0),
+            )?;
+        expect_eq!(change.charge, usd!(10.01));
+        Ok(())
+    }
+
+    #[test]
+    fn change_plan__downgrade() -> Result<()> {
+        let change = Proration::new(ProrationMethod::PerSecond).change_plan(
+            cad!(100),
+            cad!(40),
+            at(2024, 4, 1, 0),
+            at(2024, 5, 1, 0),
+            at(2024, 4, 25, 12),
+        )?;
+        // 5.5 of 30 days remaining.
+        expect_eq!(change.credit, cad!(18.33));
+        expect_eq!(change.charge, cad!(7.33));
+        expect_eq!(change.net, cad!(-11.00));
+        Ok(())
+    }
+
+    #[test]
+    fn change_plan__whole_months() -> Result<()> {
+        let change = Proration::new(ProrationMethod::WholeMonths).change_plan(
+            usd!(120),
+            usd!(240),
+            at(2024, 1, 1, 0),
+            at(2025, 1, 1, 0),
+            at(2024, 3, 15, 0),
+        )?;
+        // March 15 to December 15 is 9 whole months.
+        expect_eq!((change.remaining, change.length), (9, 12));
+        expect_eq!(change.credit, usd!(90));
+        expect_eq!(change.charge, usd!(180));
+        expect_eq!(change.fraction, dec!(0.75));
+        Ok(())
+    }
+
+    #[test]
+    fn change_plan__boundaries() -> Result<()> {
+        let proration = Proration::default();
+        let (start, end) = (at(2024, 2, 1, 0), at(2024, 3, 1, 0));
+        let change = proration.change_plan(usd!(29), usd!(58), start, end, start)?;
+        expect_eq!(change.credit, usd!(29));
+        expect_eq!(change.charge, usd!(58));
+        let change = proration.change_plan(usd!(29), usd!(58), start, end, end)?;
+        expect!(change.credit.is_zero());
+        expect!(change.charge.is_zero());
+        Ok(())
+    }
+
+    #[test]
+    fn change_plan__invalid__returns_err() -> Result<()> {
+        let proration = Proration::default();
+        let (start, end) = (at(2024, 2, 1, 0), at(2024, 3, 1, 0));
+        expect_eq!(
+            expect_err!(proration.change_plan(usd!(1), cad!(2), start, end, start)),
+            Error::MismatchedCurrency
+        );
+        expect_err!(proration.change_plan(usd!(1), usd!(2), end, start, start));
+        expect_err!(proration.change_plan(usd!(1), usd!(2), start, end, at(2024, 3, 2, 0)));
+        expect_err!(Proration::new(ProrationMethod::WholeMonths).change_plan(
+            usd!(1),
+            usd!(2),
+            start,
+            at(2024, 3, 15, 0),
+            start
+        ));
+        // Less than one whole day.
+        expect_err!(proration.change_plan(usd!(1), usd!(2), start, at(2024, 2, 1, 12), start));
+        Ok(())
+    }
+}
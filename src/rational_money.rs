@@ -0,0 +1,485 @@
+use crate::currency;
+use crate::currency::Currency;
+use crate::error::Error;
+use crate::fractional_money::FractionalMoney;
+use crate::money::Money;
+use crate::rounding::RoundingMode;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use rust_decimal::Decimal;
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::iter;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// A monetary value in a certain currency, represented exactly as a ratio of two (arbitrarily
+/// large) integers, e.g., 1/3 USD.
+///
+/// Unlike `FractionalMoney`, which is limited to the 28 significant digits of `Decimal`, a long
+/// chain of multiplications and divisions never loses precision, so `(x / 3) * 3 == x` always
+/// holds. The value is only collapsed to a `Decimal` when it is rounded to `Money`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RationalMoney {
+    /// The exact amount, which is always kept in lowest terms.
+    value: BigRational,
+    currency: Currency,
+}
+
+impl RationalMoney {
+    /// Creates a new exact amount of `numerator / denominator` of the given currency. Returns an
+    /// error if `denominator` is zero, or if `currency` is `ZeroNone` and the amount is non-zero.
+    pub fn new(numerator: BigInt, denominator: BigInt, currency: Currency) -> Result<Self, Error> {
+        if denominator.is_zero() {
+            return Err(Error::DivisionByZero);
+        }
+        Self::from_ratio(BigRational::new(numerator, denominator), currency)
+    }
+
+    /// Creates a new exact amount of the given currency. Returns an error if `currency` is
+    /// `ZeroNone` and the amount is non-zero.
+    pub fn from_ratio(value: BigRational, currency: Currency) -> Result<Self, Error> {
+        if let Currency::ZeroNone = currency {
+            return if value.is_zero() {
+                Ok(Self::default())
+            } else {
+                Err(Error::ZeroCurrencyWithNonZeroAmount)
+            };
+        }
+        Ok(Self { value, currency })
+    }
+
+    /// The exact amount.
+    pub fn ratio(&self) -> &BigRational {
+        &self.value
+    }
+
+    /// The numerator of the amount in lowest terms. It carries the sign of the amount.
+    pub fn numerator(&self) -> &BigInt {
+        self.value.numer()
+    }
+
+    /// The denominator of the amount in lowest terms. It is always positive.
+    pub fn denominator(&self) -> &BigInt {
+        self.value.denom()
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    /// Attempts to add another monetary value to this one. Returns an error if the currencies do
+    /// not match.
+    pub fn try_add(&self, rhs: &Self) -> Result<Self, Error> {
+        let currency = currency::combine_currency(self.currency, rhs.currency)?;
+        Ok(Self {
+            value: &self.value + &rhs.value,
+            currency,
+        })
+    }
+
+    /// Attempts to subtract another monetary value from this one. Returns an error if the
+    /// currencies do not match.
+    pub fn try_subtract(&self, rhs: &Self) -> Result<Self, Error> {
+        let currency = currency::combine_currency(self.currency, rhs.currency)?;
+        Ok(Self {
+            value: &self.value - &rhs.value,
+            currency,
+        })
+    }
+
+    /// Round to the maximum precision allowed by the currency and return a Money object. The
+    /// rounding method is "Banker's rounding" a.k.a. "midpoint nearest even".
+    ///
+    /// Returns an `Overflow` error if the rounded value cannot be represented by `Decimal`.
+    pub fn round(&self) -> Result<Money, Error> {
+        self.round_with(RoundingMode::MidpointNearestEven)
+    }
+
+    /// Similar to `round()` except that the rounding method is "midpoint away from zero".
+    pub fn round_up(&self) -> Result<Money, Error> {
+        self.round_with(RoundingMode::MidpointAwayFromZero)
+    }
+
+    /// Similar to `round()` except that the rounding method is given by `mode`.
+    pub fn round_with(&self, mode: RoundingMode) -> Result<Money, Error> {
+        let precision = self.currency.max_precision();
+        let scaled = &self.value * BigRational::from_integer(BigInt::from(10).pow(precision));
+        let units = round_to_integer(&scaled, mode)
+            .to_i128()
+            .ok_or(Error::Overflow)?;
+
+        Money::from_minor_units(units, self.currency)
+    }
+
+    /// Returns true if the amount is zero, regardless of currency.
+    pub fn is_zero(&self) -> bool {
+        self.value.is_zero()
+    }
+
+    /// Returns true if the amount > 0.
+    pub fn is_positive(&self) -> bool {
+        self.value.is_positive()
+    }
+
+    /// Returns true if the amount < 0.
+    pub fn is_negative(&self) -> bool {
+        self.value.is_negative()
+    }
+
+    /// Creates a zero-valued `RationalMoney` with `ZeroNone` currency.
+    pub fn zero() -> Self {
+        Self::default()
+    }
+}
+
+/// Rounds `value` to an integer using the given rounding method.
+fn round_to_integer(value: &BigRational, mode: RoundingMode) -> BigInt {
+    let truncated = value.trunc().to_integer();
+    let fraction = (value - BigRational::from_integer(truncated.clone())).abs();
+    if fraction.is_zero() {
+        return truncated;
+    }
+
+    let half = BigRational::new(BigInt::one(), BigInt::from(2));
+    let away_from_zero = match mode {
+        RoundingMode::MidpointNearestEven => {
+            let is_odd = !(&truncated % BigInt::from(2)).is_zero();
+            fraction > half || (fraction == half && is_odd)
+        }
+        RoundingMode::MidpointAwayFromZero => fraction >= half,
+        RoundingMode::MidpointTowardZero => fraction > half,
+        RoundingMode::ToZero => false,
+        RoundingMode::AwayFromZero => true,
+        RoundingMode::ToNegativeInfinity => value.is_negative(),
+        RoundingMode::ToPositiveInfinity => value.is_positive(),
+    };
+
+    if !away_from_zero {
+        truncated
+    } else if value.is_negative() {
+        truncated - BigInt::one()
+    } else {
+        truncated + BigInt::one()
+    }
+}
+
+/// Converts a `Decimal` to an exact ratio.
+pub(crate) fn decimal_to_ratio(value: Decimal) -> BigRational {
+    BigRational::new(
+        BigInt::from(value.mantissa()),
+        BigInt::from(10).pow(value.scale()),
+    )
+}
+
+impl Default for RationalMoney {
+    fn default() -> Self {
+        Self {
+            value: BigRational::zero(),
+            currency: Currency::ZeroNone,
+        }
+    }
+}
+
+impl From<FractionalMoney> for RationalMoney {
+    fn from(money: FractionalMoney) -> Self {
+        Self {
+            value: decimal_to_ratio(money.amount()),
+            currency: money.currency(),
+        }
+    }
+}
+
+impl From<Money> for RationalMoney {
+    fn from(money: Money) -> Self {
+        Self::from(FractionalMoney::from(money))
+    }
+}
+
+impl Display for RationalMoney {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.currency == Currency::ZeroNone {
+            write!(f, "0")
+        } else {
+            write!(f, "{} {:?}", self.value, self.currency)
+        }
+    }
+}
+
+impl Add for RationalMoney {
+    type Output = RationalMoney;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.try_add(&rhs).unwrap()
+    }
+}
+
+impl AddAssign for RationalMoney {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = self.try_add(&rhs).unwrap();
+    }
+}
+
+impl Sub for RationalMoney {
+    type Output = RationalMoney;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.try_subtract(&rhs).unwrap()
+    }
+}
+
+impl SubAssign for RationalMoney {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = self.try_subtract(&rhs).unwrap();
+    }
+}
+
+impl Mul<BigRational> for RationalMoney {
+    type Output = RationalMoney;
+
+    fn mul(self, scalar: BigRational) -> Self::Output {
+        Self {
+            value: self.value * scalar,
+            currency: self.currency,
+        }
+    }
+}
+
+impl Mul<Decimal> for RationalMoney {
+    type Output = RationalMoney;
+
+    fn mul(self, scalar: Decimal) -> Self::Output {
+        self * decimal_to_ratio(scalar)
+    }
+}
+
+impl MulAssign<Decimal> for RationalMoney {
+    fn mul_assign(&mut self, rhs: Decimal) {
+        self.value *= decimal_to_ratio(rhs);
+    }
+}
+
+impl Div<BigRational> for RationalMoney {
+    type Output = RationalMoney;
+
+    fn div(self, scalar: BigRational) -> Self::Output {
+        Self {
+            value: self.value / scalar,
+            currency: self.currency,
+        }
+    }
+}
+
+impl Div<Decimal> for RationalMoney {
+    type Output = RationalMoney;
+
+    fn div(self, scalar: Decimal) -> Self::Output {
+        self / decimal_to_ratio(scalar)
+    }
+}
+
+impl DivAssign<Decimal> for RationalMoney {
+    fn div_assign(&mut self, rhs: Decimal) {
+        self.value /= decimal_to_ratio(rhs);
+    }
+}
+
+impl Neg for RationalMoney {
+    type Output = RationalMoney;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            value: -self.value,
+            currency: self.currency,
+        }
+    }
+}
+
+/// If the iterator is empty, then the special `ZeroNone` currency will be the result.
+impl iter::Sum for RationalMoney {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Default::default(), Add::add)
+    }
+}
+
+impl Ord for RationalMoney {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if currency::combine_currency(self.currency, other.currency).is_err() {
+            panic!("tried to compare different types of currency")
+        }
+        self.value.cmp(&other.value)
+    }
+}
+
+impl PartialOrd for RationalMoney {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::{cad, usd};
+    use anyhow::Result;
+    use expecting::*;
+    use rust_decimal_macros::dec;
+
+    fn usd(numerator: i64, denominator: i64) -> RationalMoney {
+        RationalMoney::new(numerator.into(), denominator.into(), Currency::USD).unwrap()
+    }
+
+    #[test]
+    fn new__normalizes() -> Result<()> {
+        let a = usd(2, -6);
+        expect_eq!(a.numerator(), &BigInt::from(-1));
+        expect_eq!(a.denominator(), &BigInt::from(3));
+        expect_eq!(a.to_string(), "-1/3 USD");
+        Ok(())
+    }
+
+    #[test]
+    fn new__zero_denominator__returns_err() -> Result<()> {
+        let e = expect_err!(RationalMoney::new(1.into(), 0.into(), Currency::USD));
+        expect_eq!(e, Error::DivisionByZero);
+        Ok(())
+    }
+
+    #[test]
+    fn new__zero_currency_with_non_zero_amount__returns_err() -> Result<()> {
+        let e = expect_err!(RationalMoney::new(1.into(), 3.into(), Currency::ZeroNone));
+        expect_eq!(e, Error::ZeroCurrencyWithNonZeroAmount);
+        Ok(())
+    }
+
+    #[test]
+    fn from_money() -> Result<()> {
+        let a = RationalMoney::from(usd!(13.37));
+        expect_eq!(a.round()?, usd!(13.37));
+        expect_eq!(a, usd(1337, 100));
+        Ok(())
+    }
+
+    #[test]
+    fn divide_then_multiply__is_exact() -> Result<()> {
+        let a = RationalMoney::from(usd!(1));
+        let b = a.clone() / dec!(3) * dec!(3);
+        expect_eq!(b, a);
+
+        let mut c = RationalMoney::from(usd!(100));
+        for _ in 0..50 {
+            c /= dec!(7);
+        }
+        for _ in 0..50 {
+            c *= dec!(7);
+        }
+        expect_eq!(c.round()?, usd!(100));
+        Ok(())
+    }
+
+    #[test]
+    fn round() -> Result<()> {
+        expect_eq!(usd(1, 3).round()?, usd!(0.33));
+        expect_eq!(usd(2, 3).round()?, usd!(0.67));
+        expect_eq!(usd(1, 200).round()?, usd!(0.00));
+        expect_eq!(usd(3, 200).round()?, usd!(0.02));
+        expect_eq!(usd(-3, 200).round()?, usd!(-0.02));
+        expect_eq!(usd(5, 1).round()?.to_string(), "5.00 USD");
+        expect_eq!(RationalMoney::zero().round()?, Money::zero());
+        Ok(())
+    }
+
+    #[test]
+    fn round_up() -> Result<()> {
+        expect_eq!(usd(1, 200).round_up()?, usd!(0.01));
+        expect_eq!(usd(-1, 200).round_up()?, usd!(-0.01));
+        expect_eq!(usd(1, 3).round_up()?, usd!(0.33));
+        Ok(())
+    }
+
+    #[test]
+    fn round_with() -> Result<()> {
+        let value = usd(-203, 200);
+        expect_eq!(
+            value.round_with(RoundingMode::MidpointTowardZero)?,
+            usd!(-1.01)
+        );
+        expect_eq!(value.round_with(RoundingMode::ToZero)?, usd!(-1.01));
+        expect_eq!(value.round_with(RoundingMode::AwayFromZero)?, usd!(-1.02));
+        expect_eq!(
+            value.round_with(RoundingMode::ToNegativeInfinity)?,
+            usd!(-1.02)
+        );
+        expect_eq!(
+            value.round_with(RoundingMode::ToPositiveInfinity)?,
+            usd!(-1.01)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn round__overflow__returns_err() -> Result<()> {
+        let huge = BigInt::from(10).pow(40);
+        let a = expect_ok!(RationalMoney::new(huge, 1.into(), Currency::USD));
+        expect_eq!(expect_err!(a.round()), Error::Overflow);
+        Ok(())
+    }
+
+    #[test]
+    fn add_and_subtract() -> Result<()> {
+        expect_eq!(usd(1, 3) + usd(1, 6), usd(1, 2));
+        expect_eq!(usd(1, 3) - usd(1, 2), usd(-1, 6));
+        expect_eq!(usd(1, 3) + RationalMoney::zero(), usd(1, 3));
+        expect_eq!(-usd(1, 3), usd(-1, 3));
+        Ok(())
+    }
+
+    #[test]
+    fn try_add__mismatched_currency__returns_err() -> Result<()> {
+        let c = RationalMoney::from(cad!(1));
+        expect_eq!(
+            expect_err!(usd(1, 3).try_add(&c)),
+            Error::MismatchedCurrency
+        );
+        expect_eq!(
+            expect_err!(usd(1, 3).try_subtract(&c)),
+            Error::MismatchedCurrency
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn multiply_and_divide_by_ratio() -> Result<()> {
+        let two_thirds = BigRational::new(2.into(), 3.into());
+        expect_eq!(usd(1, 1) * two_thirds.clone(), usd(2, 3));
+        expect_eq!(usd(1, 1) / two_thirds, usd(3, 2));
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic]
+    fn divide_by_0() {
+        let _ = usd(1, 1) / dec!(0);
+    }
+
+    #[test]
+    fn sum() -> Result<()> {
+        let sum: RationalMoney = vec![usd(1, 3), usd(1, 3), usd(1, 3)].into_iter().sum();
+        expect_eq!(sum, usd(1, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn compare() -> Result<()> {
+        expect!(usd(1, 3) < usd(1, 2));
+        expect!(usd(-1, 3) > usd(-1, 2));
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic]
+    fn compare_different_currencies() {
+        let _ = usd(1, 3) < RationalMoney::from(cad!(1));
+    }
+}
@@ -0,0 +1,218 @@
+use crate::error::Error;
+use crate::fractional_money::FractionalMoney;
+use crate::money::Money;
+use rust_decimal::RoundingStrategy;
+use std::fmt::{Display, Formatter};
+
+/// The method used to round a `FractionalMoney` to a valid denomination of its currency.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// "Banker's rounding": a midpoint is rounded to the nearest even number. This is the method
+    /// used by `FractionalMoney::round()`.
+    #[default]
+    MidpointNearestEven,
+    /// "Standard" rounding: a midpoint is rounded away from zero. This is the method used by
+    /// `FractionalMoney::round_up()`.
+    MidpointAwayFromZero,
+    /// A midpoint is rounded towards zero.
+    MidpointTowardZero,
+    /// Always round towards zero, i.e., truncate.
+    ToZero,
+    /// Always round away from zero.
+    AwayFromZero,
+    /// Always round down, i.e., floor.
+    ToNegativeInfinity,
+    /// Always round up, i.e., ceiling.
+    ToPositiveInfinity,
+}
+
+impl RoundingMode {
+    pub(crate) fn strategy(self) -> RoundingStrategy {
+        match self {
+            Self::MidpointNearestEven => RoundingStrategy::MidpointNearestEven,
+            Self::MidpointAwayFromZero => RoundingStrategy::MidpointAwayFromZero,
+            Self::MidpointTowardZero => RoundingStrategy::MidpointTowardZero,
+            Self::ToZero => RoundingStrategy::ToZero,
+            Self::AwayFromZero => RoundingStrategy::AwayFromZero,
+            Self::ToNegativeInfinity => RoundingStrategy::ToNegativeInfinity,
+            Self::ToPositiveInfinity => RoundingStrategy::ToPositiveInfinity,
+        }
+    }
+}
+
+impl Display for RoundingMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::MidpointNearestEven => "midpoint nearest even",
+            Self::MidpointAwayFromZero => "midpoint away from zero",
+            Self::MidpointTowardZero => "midpoint toward zero",
+            Self::ToZero => "toward zero",
+            Self::AwayFromZero => "away from zero",
+            Self::ToNegativeInfinity => "toward negative infinity",
+            Self::ToPositiveInfinity => "toward positive infinity",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A single value rounded by a `RoundingAccumulator`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RoundedLine {
+    /// The rounded value, including any adjustment.
+    pub amount: Money,
+    /// The difference between `amount` and the value rounded on its own, i.e., how much of the
+    /// carried-forward residual was applied to this line. Usually zero, or one minor unit.
+    pub adjustment: Money,
+}
+
+/// Rounds a sequence of values (e.g., the lines of an invoice) such that the sum of the rounded
+/// values always equals the rounded sum of the exact values. This is done by carrying the residual
+/// of each rounding forward to the next value.
+///
+/// Each pushed value must be of the same currency (or `ZeroNone`).
+#[derive(Clone, Debug, Default)]
+pub struct RoundingAccumulator {
+    mode: RoundingMode,
+    exact_total: FractionalMoney,
+    rounded_total: Money,
+}
+
+impl RoundingAccumulator {
+    pub fn new(mode: RoundingMode) -> Self {
+        Self {
+            mode,
+            ..Default::default()
+        }
+    }
+
+    /// Rounds the next value, applying any residual carried forward from previous values. Returns
+    /// an error if the currency differs from the previous values.
+    pub fn push(&mut self, value: &FractionalMoney) -> Result<RoundedLine, Error> {
+        let exact_total = self.exact_total.try_add(value)?;
+        let rounded_total = exact_total.round_with(self.mode);
+        let amount = rounded_total.try_subtract(&self.rounded_total)?;
+        let adjustment = amount.try_subtract(&value.round_with(self.mode))?;
+
+        self.exact_total = exact_total;
+        self.rounded_total = rounded_total;
+
+        Ok(RoundedLine { amount, adjustment })
+    }
+
+    /// The exact sum of all values pushed so far.
+    pub fn exact_total(&self) -> FractionalMoney {
+        self.exact_total
+    }
+
+    /// The sum of all rounded values returned so far, which is always equal to `exact_total()`
+    /// rounded.
+    pub fn rounded_total(&self) -> Money {
+        self.rounded_total
+    }
+
+    /// The residual that has not (yet) been accounted for by rounding, i.e.,
+    /// `exact_total() - rounded_total()`.
+    pub fn residual(&self) -> FractionalMoney {
+        self.exact_total
+            .try_subtract_money(&self.rounded_total)
+            .unwrap()
+    }
+}
+
+/// Rounds all of `values` using a `RoundingAccumulator`, so that the sum of the returned amounts is
+/// equal to the rounded sum of `values`. Returns an error if the values are of different
+/// currencies.
+pub fn round_all(
+    values: &[FractionalMoney],
+    mode: RoundingMode,
+) -> Result<Vec<RoundedLine>, Error> {
+    let mut accumulator = RoundingAccumulator::new(mode);
+    values.iter().map(|v| accumulator.push(v)).collect()
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::{usd, Currency};
+    use anyhow::Result;
+    use expecting::*;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    fn usd(d: &str) -> FractionalMoney {
+        FractionalMoney::new(Decimal::from_str_exact(d).unwrap(), Currency::USD).unwrap()
+    }
+
+    #[test]
+    fn round_all__thirds__sum_to_rounded_total() -> Result<()> {
+        let third = usd("1") / dec!(3);
+        let lines = expect_ok!(round_all(
+            &[third, third, third],
+            RoundingMode::MidpointNearestEven
+        ));
+        let amounts: Vec<Money> = lines.iter().map(|l| l.amount).collect();
+        expect_eq!(amounts, vec![usd!(0.33), usd!(0.34), usd!(0.33)]);
+        let adjustments: Vec<Money> = lines.iter().map(|l| l.adjustment).collect();
+        expect_eq!(adjustments, vec![usd!(0), usd!(0.01), usd!(0)]);
+        expect_eq!(lines.iter().map(|l| l.amount).sum::<Money>(), usd!(1));
+        Ok(())
+    }
+
+    #[test]
+    fn round_all__naive_rounding_drifts() -> Result<()> {
+        let values = [usd("0.005"), usd("0.005"), usd("0.005"), usd("0.005")];
+        let naive: Money = values.iter().map(|v| v.round_up()).sum();
+        expect_eq!(naive, usd!(0.04));
+
+        let lines = expect_ok!(round_all(&values, RoundingMode::MidpointAwayFromZero));
+        let amounts: Vec<Money> = lines.iter().map(|l| l.amount).collect();
+        expect_eq!(
+            amounts,
+            vec![usd!(0.01), usd!(0.00), usd!(0.01), usd!(0.00)]
+        );
+        expect_eq!(lines.iter().map(|l| l.amount).sum::<Money>(), usd!(0.02));
+        Ok(())
+    }
+
+    #[test]
+    fn round_all__empty() -> Result<()> {
+        let lines = expect_ok!(round_all(&[], RoundingMode::default()));
+        expect!(lines.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn round_all__mismatched_currency__returns_err() -> Result<()> {
+        let cad = FractionalMoney::new(dec!(1), Currency::CAD)?;
+        let e = expect_err!(round_all(&[usd("1"), cad], RoundingMode::default()));
+        expect_eq!(e, Error::MismatchedCurrency);
+        Ok(())
+    }
+
+    #[test]
+    fn accumulator__tracks_totals() -> Result<()> {
+        let mut acc = RoundingAccumulator::new(RoundingMode::ToZero);
+        expect_ok!(acc.push(&usd("1.119")));
+        expect_ok!(acc.push(&usd("2.009")));
+        expect_eq!(acc.exact_total(), usd("3.128"));
+        expect_eq!(acc.rounded_total(), usd!(3.12));
+        expect_eq!(acc.residual(), usd("0.008"));
+
+        let line = expect_ok!(acc.push(&usd("0.002")));
+        expect_eq!(line.amount, usd!(0.01));
+        expect_eq!(line.adjustment, usd!(0.01));
+        expect_eq!(acc.rounded_total(), usd!(3.13));
+        Ok(())
+    }
+
+    #[test]
+    fn accumulator__negative_values() -> Result<()> {
+        let mut acc = RoundingAccumulator::new(RoundingMode::MidpointNearestEven);
+        let a = expect_ok!(acc.push(&usd("-0.333")));
+        let b = expect_ok!(acc.push(&usd("-0.333")));
+        let c = expect_ok!(acc.push(&usd("-0.334")));
+        expect_eq!(a.amount + b.amount + c.amount, usd!(-1));
+        Ok(())
+    }
+}
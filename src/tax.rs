@@ -0,0 +1,392 @@
+//! Sales tax calculations, with prices that either exclude or include tax.
+
+pub mod canada;
+
+use crate::currency;
+use crate::currency::Currency;
+use crate::error::Error;
+use crate::fractional_money::FractionalMoney;
+use crate::money::Money;
+use crate::rounding::{RoundingAccumulator, RoundingMode};
+use rust_decimal::Decimal;
+
+/// A named tax rate, e.g., `TaxRate::new("GST", dec!(0.05))` for 5% GST.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaxRate {
+    pub name: String,
+    pub rate: Decimal,
+    /// If true, the tax is charged on the price plus the taxes listed before it (a "tax on tax"),
+    /// rather than on the price alone.
+    pub compound: bool,
+}
+
+impl TaxRate {
+    pub fn new(name: impl Into<String>, rate: Decimal) -> Self {
+        Self {
+            name: name.into(),
+            rate,
+            compound: false,
+        }
+    }
+
+    /// A tax charged on the price plus the taxes listed before it.
+    pub fn compound(name: impl Into<String>, rate: Decimal) -> Self {
+        Self {
+            compound: true,
+            ..Self::new(name, rate)
+        }
+    }
+}
+
+/// Whether prices exclude or include tax.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum PriceMode {
+    /// Prices are net of tax, which is added to them.
+    #[default]
+    TaxExclusive,
+    /// Prices are gross, i.e., they include tax, which is backed out of them.
+    TaxInclusive,
+}
+
+/// Where taxes are rounded to the minor unit of the currency.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum RoundingLevel {
+    /// Each tax is rounded on each line, and the invoice's tax is the sum of the rounded lines.
+    #[default]
+    PerLine,
+    /// Each tax is rounded once on the invoice's total. The line amounts are rounded so that they
+    /// add up to it, with rounding differences carried from line to line.
+    PerInvoice,
+}
+
+/// The amount of a single tax.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaxAmount {
+    pub name: String,
+    pub amount: Money,
+}
+
+/// The breakdown of a price (or an invoice) into its net amount and taxes, where
+/// `net + taxes == gross` exactly.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaxBreakdown {
+    pub net: Money,
+    /// The amount of each tax, in the order of the rates.
+    pub taxes: Vec<TaxAmount>,
+    pub gross: Money,
+}
+
+impl TaxBreakdown {
+    /// The sum of all taxes.
+    pub fn total_tax(&self) -> Money {
+        self.taxes.iter().map(|t| t.amount).sum()
+    }
+
+    /// The amount of the tax with the given name, if any.
+    pub fn tax(&self, name: &str) -> Option<Money> {
+        self.taxes.iter().find(|t| t.name == name).map(|t| t.amount)
+    }
+}
+
+/// The taxes on each line of an invoice, and on the invoice as a whole.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvoiceTax {
+    pub lines: Vec<TaxBreakdown>,
+    /// The sums of the lines' amounts.
+    pub total: TaxBreakdown,
+}
+
+/// A set of tax rates and the rules for applying them to prices. Taxes are calculated exactly,
+/// as `FractionalMoney`, and only rounded at the configured `RoundingLevel`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SalesTax {
+    rates: Vec<TaxRate>,
+    mode: PriceMode,
+    level: RoundingLevel,
+    rounding: RoundingMode,
+}
+
+impl SalesTax {
+    /// Creates tax-exclusive sales tax with the given rates, rounded per line using the default
+    /// rounding method.
+    pub fn new(rates: Vec<TaxRate>) -> Self {
+        Self {
+            rates,
+            mode: PriceMode::default(),
+            level: RoundingLevel::default(),
+            rounding: RoundingMode::default(),
+        }
+    }
+
+    pub fn with_mode(mut self, mode: PriceMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_rounding_level(mut self, level: RoundingLevel) -> Self {
+        self.level = level;
+        self
+    }
+
+    pub fn with_rounding(mut self, rounding: RoundingMode) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    pub fn rates(&self) -> &[TaxRate] {
+        &self.rates
+    }
+
+    pub fn mode(&self) -> PriceMode {
+        self.mode
+    }
+
+    /// Calculates the taxes on a single price.
+    pub fn apply(&self, price: Money) -> Result<TaxBreakdown, Error> {
+        Ok(self.apply_invoice(&[price])?.total)
+    }
+
+    /// Calculates the taxes on each line of an invoice and on the invoice as a whole. Returns an
+    /// error if the lines' currencies do not match.
+    pub fn apply_invoice(&self, lines: &[Money]) -> Result<InvoiceTax, Error> {
+        let currency = lines.iter().try_fold(Currency::ZeroNone, |acc, line| {
+            currency::combine_currency(acc, line.currency())
+        })?;
+        let gross_factor = self.gross_factor()?;
+
+        // The exact amount of each tax on each line.
+        let mut exact = vec![];
+        for line in lines {
+            let net = match self.mode {
+                PriceMode::TaxExclusive => FractionalMoney::from(*line),
+                PriceMode::TaxInclusive => {
+                    let amount = line
+                        .amount()
+                        .checked_div(gross_factor)
+                        .ok_or(Error::Overflow)?;
+                    FractionalMoney::new(amount, currency)?
+                }
+            };
+            exact.push(self.exact_taxes(&net)?);
+        }
+
+        // The rounded amount of each tax on each line.
+        let mut rounded: Vec<Vec<Money>> = vec![vec![]; lines.len()];
+        for i in 0..self.rates.len() {
+            let mut accumulator = RoundingAccumulator::new(self.rounding);
+            for (line, taxes) in exact.iter().enumerate() {
+                let amount = match self.level {
+                    RoundingLevel::PerLine => taxes[i].round_with(self.rounding),
+                    RoundingLevel::PerInvoice => accumulator.push(&taxes[i])?.amount,
+                };
+                rounded[line].push(amount);
+            }
+        }
+
+        let breakdowns = lines
+            .iter()
+            .zip(rounded)
+            .map(|(line, taxes)| self.breakdown(*line, taxes))
+            .collect::<Result<Vec<_>, Error>>()?;
+        let zero = Money::new(Decimal::ZERO, currency)?;
+        let mut total = TaxBreakdown {
+            net: zero,
+            taxes: self.tax_amounts(vec![zero; self.rates.len()]),
+            gross: zero,
+        };
+        for line in &breakdowns {
+            total.net = total.net.try_add(&line.net)?;
+            total.gross = total.gross.try_add(&line.gross)?;
+            for (sum, tax) in total.taxes.iter_mut().zip(&line.taxes) {
+                sum.amount = sum.amount.try_add(&tax.amount)?;
+            }
+        }
+        Ok(InvoiceTax {
+            lines: breakdowns,
+            total,
+        })
+    }
+
+    /// The exact amount of each tax on a net amount.
+    fn exact_taxes(&self, net: &FractionalMoney) -> Result<Vec<FractionalMoney>, Error> {
+        let mut taxes = vec![];
+        let mut base = *net;
+        for rate in &self.rates {
+            let taxed = if rate.compound { base } else { *net };
+            let amount = taxed
+                .amount()
+                .checked_mul(rate.rate)
+                .ok_or(Error::Overflow)?;
+            let tax = FractionalMoney::new(amount, net.currency())?;
+            base = base.try_add(&tax)?;
+            taxes.push(tax);
+        }
+        Ok(taxes)
+    }
+
+    /// The ratio of a gross price to its net price.
+    fn gross_factor(&self) -> Result<Decimal, Error> {
+        let mut factor = Decimal::ONE;
+        for rate in &self.rates {
+            let taxed = if rate.compound { factor } else { Decimal::ONE };
+            factor = taxed
+                .checked_mul(rate.rate)
+                .and_then(|tax| factor.checked_add(tax))
+                .ok_or(Error::Overflow)?;
+        }
+        if factor.is_zero() {
+            return Err(Error::DivisionByZero);
+        }
+        Ok(factor)
+    }
+
+    fn breakdown(&self, price: Money, taxes: Vec<Money>) -> Result<TaxBreakdown, Error> {
+        let total_tax = taxes
+            .iter()
+            .try_fold(Money::new(Decimal::ZERO, price.currency())?, |acc, t| {
+                acc.try_add(t)
+            })?;
+        let (net, gross) = match self.mode {
+            PriceMode::TaxExclusive => (price, price.try_add(&total_tax)?),
+            PriceMode::TaxInclusive => (price.try_subtract(&total_tax)?, price),
+        };
+        Ok(TaxBreakdown {
+            net,
+            taxes: self.tax_amounts(taxes),
+            gross,
+        })
+    }
+
+    fn tax_amounts(&self, amounts: Vec<Money>) -> Vec<TaxAmount> {
+        self.rates
+            .iter()
+            .zip(amounts)
+            .map(|(rate, amount)| TaxAmount {
+                name: rate.name.clone(),
+                amount,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::{cad, usd};
+    use anyhow::Result;
+    use expecting::*;
+    use rust_decimal_macros::dec;
+
+    fn gst_pst() -> SalesTax {
+        SalesTax::new(vec![
+            TaxRate::new("GST", dec!(0.05)),
+            TaxRate::new("PST", dec!(0.07)),
+        ])
+    }
+
+    #[test]
+    fn apply__exclusive() -> Result<()> {
+        let breakdown = expect_ok!(gst_pst().apply(cad!(100)));
+        expect_eq!(breakdown.net, cad!(100));
+        expect_eq!(breakdown.tax("GST"), Some(cad!(5)));
+        expect_eq!(breakdown.tax("PST"), Some(cad!(7)));
+        expect_eq!(breakdown.tax("HST"), None);
+        expect_eq!(breakdown.total_tax(), cad!(12));
+        expect_eq!(breakdown.gross, cad!(112));
+        Ok(())
+    }
+
+    #[test]
+    fn apply__inclusive() -> Result<()> {
+        let tax = gst_pst().with_mode(PriceMode::TaxInclusive);
+        let breakdown = expect_ok!(tax.apply(cad!(112)));
+        expect_eq!(breakdown.net, cad!(100));
+        expect_eq!(breakdown.total_tax(), cad!(12));
+
+        let hst =
+            SalesTax::new(vec![TaxRate::new("HST", dec!(0.13))]).with_mode(PriceMode::TaxInclusive);
+        let breakdown = expect_ok!(hst.apply(cad!(10)));
+        expect_eq!(breakdown.tax("HST"), Some(cad!(1.15)));
+        expect_eq!(breakdown.net, cad!(8.85));
+        expect_eq!(breakdown.net + breakdown.total_tax(), breakdown.gross);
+        expect_eq!(breakdown.gross, cad!(10));
+        Ok(())
+    }
+
+    #[test]
+    fn apply__compound() -> Result<()> {
+        let tax = SalesTax::new(vec![
+            TaxRate::new("GST", dec!(0.05)),
+            TaxRate::compound("QST", dec!(0.095)),
+        ]);
+        let breakdown = tax.apply(cad!(100))?;
+        expect_eq!(breakdown.tax("GST"), Some(cad!(5)));
+        // 9.5% of 105.00
+        expect_eq!(breakdown.tax("QST"), Some(cad!(9.98)));
+        expect_eq!(breakdown.gross, cad!(114.98));
+
+        let inclusive = tax.with_mode(PriceMode::TaxInclusive);
+        let breakdown = inclusive.apply(cad!(114.98))?;
+        expect_eq!(breakdown.net, cad!(100));
+        Ok(())
+    }
+
+    #[test]
+    fn apply_invoice__rounding_level() -> Result<()> {
+        let lines = [usd!(0.10), usd!(0.10), usd!(0.10)];
+        let tax = SalesTax::new(vec![TaxRate::new("Sales tax", dec!(0.05))])
+            .with_rounding(RoundingMode::MidpointAwayFromZero);
+
+        let per_line = tax.apply_invoice(&lines)?;
+        expect_eq!(per_line.total.total_tax(), usd!(0.03));
+        expect!(per_line.lines.iter().all(|l| l.total_tax() == usd!(0.01)));
+
+        let per_invoice = tax
+            .with_rounding_level(RoundingLevel::PerInvoice)
+            .apply_invoice(&lines)?;
+        expect_eq!(per_invoice.total.total_tax(), usd!(0.02));
+        expect_eq!(per_invoice.total.gross, usd!(0.32));
+        let line_taxes: Vec<Money> = per_invoice.lines.iter().map(|l| l.total_tax()).collect();
+        expect_eq!(line_taxes, vec![usd!(0.01), usd!(0), usd!(0.01)]);
+        Ok(())
+    }
+
+    #[test]
+    fn apply_invoice__inclusive_totals_are_exact() -> Result<()> {
+        let lines = [cad!(9.99), cad!(4.49), cad!(0.99), cad!(19.95)];
+        for level in [RoundingLevel::PerLine, RoundingLevel::PerInvoice] {
+            let invoice = gst_pst()
+                .with_mode(PriceMode::TaxInclusive)
+                .with_rounding_level(level)
+                .apply_invoice(&lines)?;
+            expect_eq!(invoice.total.gross, cad!(35.42));
+            expect_eq!(
+                invoice.total.net + invoice.total.total_tax(),
+                invoice.total.gross
+            );
+            for line in &invoice.lines {
+                expect_eq!(line.net + line.total_tax(), line.gross);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn apply_invoice__empty() -> Result<()> {
+        let invoice = gst_pst().apply_invoice(&[])?;
+        expect_empty!(invoice.lines);
+        expect_eq!(invoice.total.gross, Money::zero());
+        expect_eq!(invoice.total.taxes.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn apply_invoice__mismatched_currency__returns_err() -> Result<()> {
+        expect_eq!(
+            expect_err!(gst_pst().apply_invoice(&[cad!(1), usd!(1)])),
+            Error::MismatchedCurrency
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,422 @@
+//! Canadian sales tax (GST, HST, PST, QST and RST) by province and date.
+//!
+//! The built-in rates are kept in a table, `RateTable::default()`, which starts on January 1,
+//! 2008. Rates which are not yet in the crate can be added with `RateTable::insert()`.
+
+use crate::currency::Currency;
+use crate::error::Error;
+use crate::money::Money;
+use crate::tax::{SalesTax, TaxBreakdown, TaxRate};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+/// A Canadian province or territory.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Province {
+    Alberta,
+    BritishColumbia,
+    Manitoba,
+    NewBrunswick,
+    NewfoundlandAndLabrador,
+    NorthwestTerritories,
+    NovaScotia,
+    Nunavut,
+    Ontario,
+    PrinceEdwardIsland,
+    Quebec,
+    Saskatchewan,
+    Yukon,
+}
+
+impl Province {
+    /// The two-letter postal abbreviation, e.g., "ON".
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Alberta => "AB",
+            Self::BritishColumbia => "BC",
+            Self::Manitoba => "MB",
+            Self::NewBrunswick => "NB",
+            Self::NewfoundlandAndLabrador => "NL",
+            Self::NorthwestTerritories => "NT",
+            Self::NovaScotia => "NS",
+            Self::Nunavut => "NU",
+            Self::Ontario => "ON",
+            Self::PrinceEdwardIsland => "PE",
+            Self::Quebec => "QC",
+            Self::Saskatchewan => "SK",
+            Self::Yukon => "YT",
+        }
+    }
+}
+
+type TableRow = (
+    Province,
+    (i32, u32, u32),
+    &'static [(&'static str, Decimal, bool)],
+);
+
+const GST: (&str, Decimal, bool) = ("GST", dec!(0.05), false);
+
+/// The rates in effect in each province from each date, as (name, rate, compound).
+const BUILT_IN_RATES: &[TableRow] = &[
+    (Province::Alberta, (2008, 1, 1), &[GST]),
+    (
+        Province::BritishColumbia,
+        (2008, 1, 1),
+        &[GST, ("PST", dec!(0.07), false)],
+    ),
+    (
+        Province::BritishColumbia,
+        (2010, 7, 1),
+        &[("HST", dec!(0.12), false)],
+    ),
+    (
+        Province::BritishColumbia,
+        (2013, 4, 1),
+        &[GST, ("PST", dec!(0.07), false)],
+    ),
+    (
+        Province::Manitoba,
+        (2008, 1, 1),
+        &[GST, ("RST", dec!(0.07), false)],
+    ),
+    (
+        Province::Manitoba,
+        (2013, 7, 1),
+        &[GST, ("RST", dec!(0.08), false)],
+    ),
+    (
+        Province::Manitoba,
+        (2019, 7, 1),
+        &[GST, ("RST", dec!(0.07), false)],
+    ),
+    (
+        Province::NewBrunswick,
+        (2008, 1, 1),
+        &[("HST", dec!(0.13), false)],
+    ),
+    (
+        Province::NewBrunswick,
+        (2016, 7, 1),
+        &[("HST", dec!(0.15), false)],
+    ),
+    (
+        Province::NewfoundlandAndLabrador,
+        (2008, 1, 1),
+        &[("HST", dec!(0.13), false)],
+    ),
+    (
+        Province::NewfoundlandAndLabrador,
+        (2016, 7, 1),
+        &[("HST", dec!(0.15), false)],
+    ),
+    (Province::NorthwestTerritories, (2008, 1, 1), &[GST]),
+    (
+        Province::NovaScotia,
+        (2008, 1, 1),
+        &[("HST", dec!(0.13), false)],
+    ),
+    (
+        Province::NovaScotia,
+        (2010, 7, 1),
+        &[("HST", dec!(0.15), false)],
+    ),
+    (
+        Province::NovaScotia,
+        (2025, 4, 1),
+        &[("HST", dec!(0.14), false)],
+    ),
+    (Province::Nunavut, (2008, 1, 1), &[GST]),
+    (
+        Province::Ontario,
+        (2008, 1, 1),
+        &[GST, ("PST", dec!(0.08), false)],
+    ),
+    (
+        Province::Ontario,
+        (2010, 7, 1),
+        &[("HST", dec!(0.13), false)],
+    ),
+    (
+        Province::PrinceEdwardIsland,
+        (2008, 1, 1),
+        &[GST, ("PST", dec!(0.10), true)],
+    ),
+    (
+        Province::PrinceEdwardIsland,
+        (2013, 4, 1),
+        &[("HST", dec!(0.14), false)],
+    ),
+    (
+        Province::PrinceEdwardIsland,
+        (2016, 10, 1),
+        &[("HST", dec!(0.15), false)],
+    ),
+    (
+        Province::Quebec,
+        (2008, 1, 1),
+        &[GST, ("QST", dec!(0.075), true)],
+    ),
+    (
+        Province::Quebec,
+        (2011, 1, 1),
+        &[GST, ("QST", dec!(0.085), true)],
+    ),
+    (
+        Province::Quebec,
+        (2012, 1, 1),
+        &[GST, ("QST", dec!(0.095), true)],
+    ),
+    (
+        Province::Quebec,
+        (2013, 1, 1),
+        &[GST, ("QST", dec!(0.09975), false)],
+    ),
+    (
+        Province::Saskatchewan,
+        (2008, 1, 1),
+        &[GST, ("PST", dec!(0.05), false)],
+    ),
+    (
+        Province::Saskatchewan,
+        (2017, 3, 23),
+        &[GST, ("PST", dec!(0.06), false)],
+    ),
+    (Province::Yukon, (2008, 1, 1), &[GST]),
+];
+
+/// The sales tax rates in effect in a province from a given date until the next entry for the
+/// same province.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RateEntry {
+    pub province: Province,
+    pub effective: NaiveDate,
+    /// All the taxes charged, including GST or HST, in the order they are applied.
+    pub rates: Vec<TaxRate>,
+}
+
+/// A table of Canadian sales tax rates by province and effective date.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RateTable {
+    entries: Vec<RateEntry>,
+}
+
+impl Default for RateTable {
+    /// The rates built into the crate.
+    fn default() -> Self {
+        let mut table = Self::empty();
+        for (province, (year, month, day), rates) in BUILT_IN_RATES {
+            let rates = rates
+                .iter()
+                .map(|(name, rate, compound)| TaxRate {
+                    name: name.to_string(),
+                    rate: *rate,
+                    compound: *compound,
+                })
+                .collect();
+            let effective = NaiveDate::from_ymd_opt(*year, *month, *day).unwrap();
+            table.insert(*province, effective, rates);
+        }
+        table
+    }
+}
+
+impl RateTable {
+    /// A table with no rates.
+    pub fn empty() -> Self {
+        Self { entries: vec![] }
+    }
+
+    /// Adds the rates in effect in `province` from `effective`, replacing any already in the
+    /// table for that date.
+    pub fn insert(&mut self, province: Province, effective: NaiveDate, rates: Vec<TaxRate>) {
+        self.entries
+            .retain(|e| !(e.province == province && e.effective == effective));
+        self.entries.push(RateEntry {
+            province,
+            effective,
+            rates,
+        });
+    }
+
+    pub fn entries(&self) -> &[RateEntry] {
+        &self.entries
+    }
+
+    /// The rates in effect in `province` on `date`. Returns an error if the table has no rates
+    /// for that date.
+    pub fn rates(&self, province: Province, date: NaiveDate) -> Result<&[TaxRate], Error> {
+        self.entries
+            .iter()
+            .filter(|e| e.province == province && e.effective <= date)
+            .max_by_key(|e| e.effective)
+            .map(|e| e.rates.as_slice())
+            .ok_or_else(|| {
+                Error::InvalidArgument(format!(
+                    "no sales tax rates for {} on {date}",
+                    province.code()
+                ))
+            })
+    }
+
+    /// The tax-exclusive sales tax in effect in `province` on `date`, which can be further
+    /// configured, e.g., for tax-inclusive prices. Returns an error if the table has no rates for
+    /// that date.
+    pub fn sales_tax(&self, province: Province, date: NaiveDate) -> Result<SalesTax, Error> {
+        Ok(SalesTax::new(self.rates(province, date)?.to_vec()))
+    }
+
+    /// Calculates the tax on a tax-exclusive price in CAD in `province` on `date`. Returns an
+    /// error if the price is not in CAD or the table has no rates for that date.
+    pub fn apply(
+        &self,
+        province: Province,
+        date: NaiveDate,
+        price: Money,
+    ) -> Result<TaxBreakdown, Error> {
+        if price.currency() != Currency::CAD {
+            return Err(Error::InvalidArgument(format!(
+                "expected a price in CAD, but got {price}"
+            )));
+        }
+        self.sales_tax(province, date)?.apply(price)
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::{cad, usd};
+    use anyhow::Result;
+    use expecting::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn apply__hst() -> Result<()> {
+        let table = RateTable::default();
+        let breakdown = expect_ok!(table.apply(Province::Ontario, date(2024, 6, 1), cad!(100)));
+        expect_eq!(breakdown.tax("HST"), Some(cad!(13)));
+        expect_eq!(breakdown.taxes.len(), 1);
+        expect_eq!(breakdown.gross, cad!(113));
+        Ok(())
+    }
+
+    #[test]
+    fn apply__gst_and_pst() -> Result<()> {
+        let table = RateTable::default();
+        let breakdown = table.apply(Province::Ontario, date(2010, 6, 30), cad!(100))?;
+        expect_eq!(breakdown.tax("GST"), Some(cad!(5)));
+        expect_eq!(breakdown.tax("PST"), Some(cad!(8)));
+
+        let breakdown = table.apply(Province::BritishColumbia, date(2013, 4, 1), cad!(100))?;
+        expect_eq!(breakdown.tax("PST"), Some(cad!(7)));
+        expect_eq!(breakdown.gross, cad!(112));
+        Ok(())
+    }
+
+    #[test]
+    fn apply__historic_compounding() -> Result<()> {
+        let table = RateTable::default();
+        let breakdown = table.apply(Province::Quebec, date(2012, 12, 31), cad!(100))?;
+        // 9.5% of 105.00
+        expect_eq!(breakdown.tax("QST"), Some(cad!(9.98)));
+
+        let breakdown = table.apply(Province::Quebec, date(2013, 1, 1), cad!(100))?;
+        // 9.975% of 100.00
+        expect_eq!(breakdown.tax("QST"), Some(cad!(9.98)));
+        let breakdown = table.apply(Province::Quebec, date(2013, 1, 1), cad!(200))?;
+        expect_eq!(breakdown.tax("QST"), Some(cad!(19.95)));
+
+        let breakdown = table.apply(Province::PrinceEdwardIsland, date(2012, 1, 1), cad!(100))?;
+        // 10% of 105.00
+        expect_eq!(breakdown.tax("PST"), Some(cad!(10.50)));
+        expect_eq!(breakdown.gross, cad!(115.50));
+        Ok(())
+    }
+
+    #[test]
+    fn apply__rate_changes() -> Result<()> {
+        let table = RateTable::default();
+        let hst = |date| {
+            table
+                .apply(Province::NovaScotia, date, cad!(100))
+                .unwrap()
+                .total_tax()
+        };
+        expect_eq!(hst(date(2010, 6, 30)), cad!(13));
+        expect_eq!(hst(date(2025, 3, 31)), cad!(15));
+        expect_eq!(hst(date(2025, 4, 1)), cad!(14));
+        Ok(())
+    }
+
+    #[test]
+    fn apply__every_province() -> Result<()> {
+        let table = RateTable::default();
+        let provinces = [
+            Province::Alberta,
+            Province::BritishColumbia,
+            Province::Manitoba,
+            Province::NewBrunswick,
+            Province::NewfoundlandAndLabrador,
+            Province::NorthwestTerritories,
+            Province::NovaScotia,
+            Province::Nunavut,
+            Province::Ontario,
+            Province::PrinceEdwardIsland,
+            Province::Quebec,
+            Province::Saskatchewan,
+            Province::Yukon,
+        ];
+        for province in provinces {
+            let breakdown = expect_ok!(table.apply(province, date(2024, 1, 1), cad!(100)));
+            expect!(breakdown.total_tax() >= cad!(5));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn apply__invalid__returns_err() -> Result<()> {
+        let table = RateTable::default();
+        expect_err!(table.apply(Province::Ontario, date(2024, 1, 1), usd!(100)));
+        expect_err!(table.apply(Province::Ontario, date(2007, 12, 31), cad!(100)));
+        Ok(())
+    }
+
+    #[test]
+    fn insert__updates_table() -> Result<()> {
+        let mut table = RateTable::default();
+        table.insert(
+            Province::Alberta,
+            date(2030, 1, 1),
+            vec![
+                TaxRate::new("GST", dec!(0.05)),
+                TaxRate::new("PST", dec!(0.03)),
+            ],
+        );
+        expect_eq!(table.rates(Province::Alberta, date(2029, 12, 31))?.len(), 1);
+        expect_eq!(
+            table
+                .apply(Province::Alberta, date(2030, 1, 1), cad!(100))?
+                .gross,
+            cad!(108)
+        );
+
+        table.insert(
+            Province::Alberta,
+            date(2030, 1, 1),
+            vec![TaxRate::new("GST", dec!(0.06))],
+        );
+        expect_eq!(
+            table
+                .apply(Province::Alberta, date(2030, 1, 1), cad!(100))?
+                .gross,
+            cad!(106)
+        );
+        Ok(())
+    }
+}
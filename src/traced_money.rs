@@ -0,0 +1,413 @@
+use crate::error::Error;
+use crate::fractional_money::FractionalMoney;
+use crate::money::Money;
+use crate::rounding::RoundingMode;
+use rust_decimal::Decimal;
+use std::fmt::{Display, Formatter, Write};
+use std::ops::{Add, Div, Mul, Sub};
+
+/// An operation recorded by `TracedMoney`, along with its operand.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Operation {
+    /// Added another (possibly traced) monetary value.
+    Add(TracedMoney),
+    /// Subtracted another (possibly traced) monetary value.
+    Subtract(TracedMoney),
+    /// Multiplied by a rate.
+    Multiply(Decimal),
+    /// Divided by a divisor.
+    Divide(Decimal),
+    /// Rounded to a valid denomination of the currency.
+    Round(RoundingMode),
+}
+
+/// A single step in the history of a `TracedMoney`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub operation: Operation,
+    /// The value after applying `operation`.
+    pub result: FractionalMoney,
+}
+
+/// A `FractionalMoney` that records every operation applied to it, so that the derivation of the
+/// final value can be explained, e.g., to an auditor. The arithmetic is exactly that of
+/// `FractionalMoney`, so the results are identical to the untraced calculation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TracedMoney {
+    label: Option<String>,
+    initial: FractionalMoney,
+    steps: Vec<Step>,
+}
+
+impl TracedMoney {
+    /// Starts tracing calculations from the given value.
+    pub fn new(value: impl Into<FractionalMoney>) -> Self {
+        Self {
+            label: None,
+            initial: value.into(),
+            steps: vec![],
+        }
+    }
+
+    /// Like `new()`, but with a label describing the value, e.g., "subtotal".
+    pub fn labeled(label: impl Into<String>, value: impl Into<FractionalMoney>) -> Self {
+        Self {
+            label: Some(label.into()),
+            ..Self::new(value)
+        }
+    }
+
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// The value before any operations were applied.
+    pub fn initial(&self) -> FractionalMoney {
+        self.initial
+    }
+
+    /// The operations applied so far, in order.
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// The current value, i.e., the result of the last operation.
+    pub fn value(&self) -> FractionalMoney {
+        self.steps.last().map_or(self.initial, |s| s.result)
+    }
+
+    /// Attempts to add another monetary value to this one. Returns an error if the currencies do
+    /// not match.
+    pub fn try_add(&self, rhs: &Self) -> Result<Self, Error> {
+        let result = self.value().try_add(&rhs.value())?;
+        Ok(self.clone().push(Operation::Add(rhs.clone()), result))
+    }
+
+    /// Attempts to subtract another monetary value from this one. Returns an error if the
+    /// currencies do not match.
+    pub fn try_subtract(&self, rhs: &Self) -> Result<Self, Error> {
+        let result = self.value().try_subtract(&rhs.value())?;
+        Ok(self.clone().push(Operation::Subtract(rhs.clone()), result))
+    }
+
+    /// Multiplies the value by a rate.
+    pub fn multiply(&self, rate: Decimal) -> Self {
+        self.clone()
+            .push(Operation::Multiply(rate), self.value() * rate)
+    }
+
+    /// Divides the value by a divisor. Panics if `divisor` is zero, as `FractionalMoney` does.
+    pub fn divide(&self, divisor: Decimal) -> Self {
+        self.clone()
+            .push(Operation::Divide(divisor), self.value() / divisor)
+    }
+
+    /// Rounds the value using the given rounding method. See `FractionalMoney::round_with()`.
+    pub fn round_with(&self, mode: RoundingMode) -> Self {
+        let rounded = self.value().round_with(mode);
+        self.clone().push(Operation::Round(mode), rounded.into())
+    }
+
+    /// Converts the current value to `Money`. This only succeeds if the value is a valid
+    /// denomination of the currency, e.g., after calling `round_with()`. See
+    /// `FractionalMoney::try_into_money()`.
+    pub fn try_into_money(&self) -> Result<Money, Error> {
+        self.value().try_into_money()
+    }
+
+    /// Renders the history of the calculation as human-readable text, with one line per step.
+    /// Traced operands that have a history of their own are shown nested in parentheses, e.g.:
+    ///
+    /// ```text
+    /// subtotal: 100.00 USD
+    ///   * 1.05 = 105.0000 USD
+    ///   + shipping: 5.00 USD = 110.0000 USD
+    ///   round (midpoint nearest even) = 110.00 USD
+    /// ```
+    pub fn explain(&self) -> String {
+        let mut out = String::new();
+        self.write_explanation(&mut out, 0);
+        out
+    }
+
+    /// Renders the history of the calculation as JSON. Amounts are rendered as strings so that no
+    /// precision is lost.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        self.write_json(&mut out);
+        out
+    }
+
+    /// Records a step, taking ownership so that the history is not copied.
+    fn push(mut self, operation: Operation, result: FractionalMoney) -> Self {
+        self.steps.push(Step { operation, result });
+        self
+    }
+
+    fn header(&self) -> String {
+        match &self.label {
+            Some(label) => format!("{label}: {}", self.initial),
+            None => self.initial.to_string(),
+        }
+    }
+
+    fn write_explanation(&self, out: &mut String, indent: usize) {
+        let pad = " ".repeat(indent);
+        writeln!(out, "{pad}{}", self.header()).unwrap();
+        for step in &self.steps {
+            let (symbol, operand) = match &step.operation {
+                Operation::Add(operand) => ("+", operand),
+                Operation::Subtract(operand) => ("-", operand),
+                Operation::Multiply(rate) => {
+                    writeln!(out, "{pad}  * {rate} = {}", step.result).unwrap();
+                    continue;
+                }
+                Operation::Divide(divisor) => {
+                    writeln!(out, "{pad}  / {divisor} = {}", step.result).unwrap();
+                    continue;
+                }
+                Operation::Round(mode) => {
+                    writeln!(out, "{pad}  round ({mode}) = {}", step.result).unwrap();
+                    continue;
+                }
+            };
+            if operand.steps.is_empty() {
+                let header = operand.header();
+                writeln!(out, "{pad}  {symbol} {header} = {}", step.result).unwrap();
+            } else {
+                writeln!(out, "{pad}  {symbol} (").unwrap();
+                operand.write_explanation(out, indent + 4);
+                writeln!(out, "{pad}  ) = {}", step.result).unwrap();
+            }
+        }
+    }
+
+    fn write_json(&self, out: &mut String) {
+        out.push_str("{\"label\":");
+        match &self.label {
+            Some(label) => write_json_string(out, label),
+            None => out.push_str("null"),
+        }
+        out.push_str(",\"initial\":");
+        write_json_money(out, &self.initial);
+        out.push_str(",\"steps\":[");
+        for (i, step) in self.steps.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            match &step.operation {
+                Operation::Add(operand) => {
+                    out.push_str("{\"operation\":\"add\",\"operand\":");
+                    operand.write_json(out);
+                }
+                Operation::Subtract(operand) => {
+                    out.push_str("{\"operation\":\"subtract\",\"operand\":");
+                    operand.write_json(out);
+                }
+                Operation::Multiply(rate) => {
+                    write!(out, "{{\"operation\":\"multiply\",\"rate\":\"{rate}\"").unwrap();
+                }
+                Operation::Divide(divisor) => {
+                    write!(out, "{{\"operation\":\"divide\",\"divisor\":\"{divisor}\"").unwrap();
+                }
+                Operation::Round(mode) => {
+                    write!(out, "{{\"operation\":\"round\",\"mode\":\"{mode}\"").unwrap();
+                }
+            }
+            out.push_str(",\"result\":");
+            write_json_money(out, &step.result);
+            out.push('}');
+        }
+        out.push_str("],\"result\":");
+        write_json_money(out, &self.value());
+        out.push('}');
+    }
+}
+
+fn write_json_money(out: &mut String, money: &FractionalMoney) {
+    write!(
+        out,
+        "{{\"amount\":\"{}\",\"currency\":\"{:?}\"}}",
+        money.amount(),
+        money.currency()
+    )
+    .unwrap();
+}
+
+fn write_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+impl From<FractionalMoney> for TracedMoney {
+    fn from(value: FractionalMoney) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<Money> for TracedMoney {
+    fn from(value: Money) -> Self {
+        Self::new(value)
+    }
+}
+
+/// Displays the current value; use `explain()` for the full history.
+impl Display for TracedMoney {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value())
+    }
+}
+
+impl Add for TracedMoney {
+    type Output = TracedMoney;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let result = self.value().try_add(&rhs.value()).unwrap();
+        self.push(Operation::Add(rhs), result)
+    }
+}
+
+impl Sub for TracedMoney {
+    type Output = TracedMoney;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        let result = self.value().try_subtract(&rhs.value()).unwrap();
+        self.push(Operation::Subtract(rhs), result)
+    }
+}
+
+impl Mul<Decimal> for TracedMoney {
+    type Output = TracedMoney;
+
+    fn mul(self, rate: Decimal) -> Self::Output {
+        let result = self.value() * rate;
+        self.push(Operation::Multiply(rate), result)
+    }
+}
+
+impl Div<Decimal> for TracedMoney {
+    type Output = TracedMoney;
+
+    fn div(self, divisor: Decimal) -> Self::Output {
+        let result = self.value() / divisor;
+        self.push(Operation::Divide(divisor), result)
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::{cad, usd};
+    use anyhow::Result;
+    use expecting::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn results_are_identical_to_untraced() -> Result<()> {
+        let untraced = ((usd!(13.37) * dec!(1.05) + usd!(2)) / dec!(3)).round();
+
+        let traced = ((TracedMoney::from(usd!(13.37)) * dec!(1.05) + usd!(2).into()) / dec!(3))
+            .round_with(RoundingMode::MidpointNearestEven);
+        expect_eq!(expect_ok!(traced.try_into_money()), untraced);
+        Ok(())
+    }
+
+    #[test]
+    fn steps() -> Result<()> {
+        let traced = TracedMoney::labeled("price", usd!(10)).multiply(dec!(0.5));
+        expect_eq!(traced.label(), Some("price"));
+        expect_eq!(traced.initial(), usd!(10).into());
+        expect_eq!(traced.value(), usd!(5).into());
+        expect_eq!(
+            traced.steps(),
+            &[Step {
+                operation: Operation::Multiply(dec!(0.5)),
+                result: usd!(5).into(),
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn try_add__mismatched_currency__returns_err() -> Result<()> {
+        let a = TracedMoney::from(usd!(1));
+        let b = TracedMoney::from(cad!(1));
+        expect_eq!(expect_err!(a.try_add(&b)), Error::MismatchedCurrency);
+        expect_eq!(expect_err!(a.try_subtract(&b)), Error::MismatchedCurrency);
+        Ok(())
+    }
+
+    #[test]
+    fn try_into_money__before_rounding__returns_err() -> Result<()> {
+        let traced = TracedMoney::from(usd!(1)).divide(dec!(3));
+        expect_err!(traced.try_into_money());
+        Ok(())
+    }
+
+    #[test]
+    fn explain() -> Result<()> {
+        let traced = TracedMoney::labeled("subtotal", usd!(100))
+            .multiply(dec!(1.05))
+            .try_add(&TracedMoney::labeled("shipping", usd!(5)))?
+            .round_with(RoundingMode::MidpointNearestEven);
+        expect_eq!(
+            traced.explain(),
+            "subtotal: 100.00 USD\n\
+            \x20 * 1.05 = 105.0000 USD\n\
+            \x20 + shipping: 5.00 USD = 110.0000 USD\n\
+            \x20 round (midpoint nearest even) = 110.00 USD\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn explain__nested() -> Result<()> {
+        let discount = TracedMoney::labeled("discount", usd!(30)).divide(dec!(4));
+        let traced = TracedMoney::new(usd!(20)).try_subtract(&discount)?;
+        expect_eq!(
+            traced.explain(),
+            "20.00 USD\n\
+            \x20 - (\n\
+            \x20   discount: 30.00 USD\n\
+            \x20     / 4 = 7.50 USD\n\
+            \x20 ) = 12.50 USD\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn to_json() -> Result<()> {
+        let traced = TracedMoney::labeled("a \"quoted\" label", usd!(1))
+            .try_add(&TracedMoney::new(usd!(2)))?
+            .divide(dec!(2))
+            .round_with(RoundingMode::MidpointAwayFromZero);
+        expect_eq!(
+            traced.to_json(),
+            "{\"label\":\"a \\\"quoted\\\" label\",\
+            \"initial\":{\"amount\":\"1.00\",\"currency\":\"USD\"},\
+            \"steps\":[\
+            {\"operation\":\"add\",\"operand\":{\"label\":null,\
+            \"initial\":{\"amount\":\"2.00\",\"currency\":\"USD\"},\"steps\":[],\
+            \"result\":{\"amount\":\"2.00\",\"currency\":\"USD\"}},\
+            \"result\":{\"amount\":\"3.00\",\"currency\":\"USD\"}},\
+            {\"operation\":\"divide\",\"divisor\":\"2\",\
+            \"result\":{\"amount\":\"1.50\",\"currency\":\"USD\"}},\
+            {\"operation\":\"round\",\"mode\":\"midpoint away from zero\",\
+            \"result\":{\"amount\":\"1.50\",\"currency\":\"USD\"}}],\
+            \"result\":{\"amount\":\"1.50\",\"currency\":\"USD\"}}"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,485 @@
+//! Time-value-of-money functions equivalent to those of spreadsheets, e.g., `PMT()`, but working on
+//! `Money`. As in spreadsheets, money paid out is negative and money received is positive.
+//!
+//! All monetary arguments must be in the same currency (or the special `ZeroNone` currency).
+
+use crate::currency;
+use crate::currency::Currency;
+use crate::error::Error;
+use crate::fractional_money::FractionalMoney;
+use crate::money::Money;
+use chrono::NaiveDate;
+use rust_decimal::{Decimal, MathematicalOps};
+
+/// The maximum number of iterations when solving for a rate.
+const MAX_ITERATIONS: usize = 100;
+
+/// When successive estimates of a rate differ by less than this, the rate has converged.
+const TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 16);
+
+/// The initial estimate when solving for a rate.
+const GUESS: Decimal = Decimal::from_parts(1, 0, 0, false, 1);
+
+/// An amount of money paid or received on a given date.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CashFlow {
+    pub date: NaiveDate,
+    pub amount: Money,
+}
+
+impl CashFlow {
+    pub fn new(date: NaiveDate, amount: Money) -> Self {
+        Self { date, amount }
+    }
+}
+
+/// When payments are made within each period.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum PaymentTiming {
+    /// At the end of each period, i.e., an ordinary annuity. Spreadsheets call this type 0.
+    #[default]
+    End,
+    /// At the beginning of each period, i.e., an annuity due. Spreadsheets call this type 1.
+    Beginning,
+}
+
+/// The present value of a series of equal payments `pmt` over `nper` periods at `rate` per
+/// period, plus a future value `fv` at the end. Returns an error if the currencies do not match,
+/// `rate` is not greater than -1, or the calculation overflows.
+pub fn pv(
+    rate: Decimal,
+    nper: u32,
+    pmt: Money,
+    fv: Money,
+    timing: PaymentTiming,
+) -> Result<FractionalMoney, Error> {
+    let currency = combine_all([&pmt, &fv])?;
+    let (growth, annuity) = factors(rate, nper, timing)?;
+    let value = checked(|| {
+        fv.amount()
+            .checked_add(pmt.amount().checked_mul(annuity)?)?
+            .checked_div(growth)
+    })?;
+    FractionalMoney::new(-value, currency)
+}
+
+/// The future value after `nper` periods at `rate` per period of a present value `pv` and a
+/// series of equal payments `pmt`. Returns an error if the currencies do not match, `rate` is not
+/// greater than -1, or the calculation overflows.
+pub fn fv(
+    rate: Decimal,
+    nper: u32,
+    pmt: Money,
+    pv: Money,
+    timing: PaymentTiming,
+) -> Result<FractionalMoney, Error> {
+    let currency = combine_all([&pmt, &pv])?;
+    let (growth, annuity) = factors(rate, nper, timing)?;
+    let value = checked(|| {
+        pv.amount()
+            .checked_mul(growth)?
+            .checked_add(pmt.amount().checked_mul(annuity)?)
+    })?;
+    FractionalMoney::new(-value, currency)
+}
+
+/// The equal payment per period which, over `nper` periods at `rate` per period, brings a present
+/// value `pv` to a future value `fv`, e.g., the payment on a loan. Returns an error if the
+/// currencies do not match, `nper` is zero, `rate` is not greater than -1, or the calculation
+/// overflows.
+pub fn pmt(
+    rate: Decimal,
+    nper: u32,
+    pv: Money,
+    fv: Money,
+    timing: PaymentTiming,
+) -> Result<FractionalMoney, Error> {
+    let currency = combine_all([&pv, &fv])?;
+    if nper == 0 {
+        return Err(Error::InvalidArgument(
+            "expected at least one period".to_string(),
+        ));
+    }
+    let (growth, annuity) = factors(rate, nper, timing)?;
+    let value = checked(|| {
+        fv.amount()
+            .checked_add(pv.amount().checked_mul(growth)?)?
+            .checked_div(annuity)
+    })?;
+    FractionalMoney::new(-value, currency)
+}
+
+/// The number of periods (possibly fractional) for equal payments `pmt` at `rate` per period to
+/// bring a present value `pv` to a future value `fv`. Returns an error if the currencies do not
+/// match, `rate` is not greater than -1, no number of periods is possible, or the calculation
+/// overflows.
+pub fn nper(
+    rate: Decimal,
+    pmt: Money,
+    pv: Money,
+    fv: Money,
+    timing: PaymentTiming,
+) -> Result<Decimal, Error> {
+    combine_all([&pmt, &pv, &fv])?;
+    check_rate(rate)?;
+    let impossible = || {
+        Error::InvalidArgument(format!(
+            "no number of periods brings {pv} to {fv} with payments of {pmt} at {rate}"
+        ))
+    };
+    let (pmt, pv, fv) = (pmt.amount(), pv.amount(), fv.amount());
+    if rate.is_zero() {
+        if pmt.is_zero() {
+            return Err(impossible());
+        }
+        return checked(|| pv.checked_add(fv)?.checked_div(-pmt));
+    }
+
+    // ln((pmt' - fv * r) / (pmt' + pv * r)) / ln(1 + r), where pmt' = pmt * (1 + r * type).
+    let pmt = checked(|| pmt.checked_mul(timing_factor(rate, timing)))?;
+    let numerator = checked(|| pmt.checked_sub(fv.checked_mul(rate)?))?;
+    let denominator = checked(|| pmt.checked_add(pv.checked_mul(rate)?))?;
+    if denominator.is_zero() {
+        return Err(impossible());
+    }
+    let ratio = checked(|| numerator.checked_div(denominator))?;
+    let periods = ratio.checked_ln().ok_or_else(impossible)?;
+    checked(|| periods.checked_div((Decimal::ONE + rate).checked_ln()?))
+}
+
+/// The net present value at `rate` per period of cash flows at the end of each of the following
+/// periods. As in spreadsheets, the first value is discounted by one period. Returns an error if
+/// the currencies do not match, `rate` is not greater than -1, or the calculation overflows.
+pub fn npv(rate: Decimal, values: &[Money]) -> Result<FractionalMoney, Error> {
+    let currency = combine_all(values)?;
+    check_rate(rate)?;
+    let discount = checked(|| Decimal::ONE.checked_div(Decimal::ONE + rate))?;
+    let value = checked(|| {
+        let mut factor = Decimal::ONE;
+        let mut total = Decimal::ZERO;
+        for value in values {
+            factor = factor.checked_mul(discount)?;
+            total = total.checked_add(value.amount().checked_mul(factor)?)?;
+        }
+        Some(total)
+    })?;
+    FractionalMoney::new(value, currency)
+}
+
+/// The internal rate of return of cash flows at regular periods, i.e., the rate per period at
+/// which their net present value (with the first value undiscounted) is zero. Returns an error if
+/// the currencies do not match, there is not at least one positive and one negative value, or no
+/// rate is found.
+pub fn irr(values: &[Money]) -> Result<Decimal, Error> {
+    combine_all(values)?;
+    check_signs(values.iter())?;
+    solve_rate(|rate| {
+        let discount = Decimal::ONE.checked_div(Decimal::ONE + rate)?;
+        let mut factor = Decimal::ONE;
+        let (mut value, mut derivative) = (Decimal::ZERO, Decimal::ZERO);
+        for (i, amount) in values.iter().enumerate() {
+            let term = amount.amount().checked_mul(factor)?;
+            value = value.checked_add(term)?;
+            derivative = derivative
+                .checked_sub(term.checked_mul(Decimal::from(i))?.checked_mul(discount)?)?;
+            factor = factor.checked_mul(discount)?;
+        }
+        Some((value, derivative))
+    })
+}
+
+/// The net present value at the annual `rate` of cash flows on arbitrary dates, discounted to the
+/// date of the first cash flow using actual days over 365. Returns an error if the currencies do
+/// not match, `rate` is not greater than -1, or the calculation overflows.
+pub fn xnpv(rate: Decimal, flows: &[CashFlow]) -> Result<FractionalMoney, Error> {
+    let currency = combine_all(flows.iter().map(|f| &f.amount))?;
+    check_rate(rate)?;
+    let value = checked(|| Some(xnpv_with_derivative(rate, flows)?.0))?;
+    FractionalMoney::new(value, currency)
+}
+
+/// The annual internal rate of return of cash flows on arbitrary dates, i.e., the rate at which
+/// their `xnpv()` is zero. Returns an error if the currencies do not match, there is not at least
+/// one positive and one negative cash flow, or no rate is found.
+pub fn xirr(flows: &[CashFlow]) -> Result<Decimal, Error> {
+    combine_all(flows.iter().map(|f| &f.amount))?;
+    check_signs(flows.iter().map(|f| &f.amount))?;
+    solve_rate(|rate| xnpv_with_derivative(rate, flows))
+}
+
+fn xnpv_with_derivative(rate: Decimal, flows: &[CashFlow]) -> Option<(Decimal, Decimal)> {
+    let Some(first) = flows.first() else {
+        return Some((Decimal::ZERO, Decimal::ZERO));
+    };
+    let base = Decimal::ONE + rate;
+    let discount = Decimal::ONE.checked_div(base)?;
+    let (mut value, mut derivative) = (Decimal::ZERO, Decimal::ZERO);
+    for flow in flows {
+        let years = Decimal::from((flow.date - first.date).num_days()) / Decimal::from(365);
+        let term = flow
+            .amount
+            .amount()
+            .checked_mul(discount.checked_powd(years)?)?;
+        value = value.checked_add(term)?;
+        derivative = derivative.checked_sub(term.checked_mul(years)?.checked_mul(discount)?)?;
+    }
+    Some((value, derivative))
+}
+
+/// Finds the rate at which `f`, which returns a value and its derivative, is zero, by Newton's
+/// method.
+fn solve_rate(f: impl Fn(Decimal) -> Option<(Decimal, Decimal)>) -> Result<Decimal, Error> {
+    let mut rate = GUESS;
+    for _ in 0..MAX_ITERATIONS {
+        let Some((value, derivative)) = f(rate) else {
+            break;
+        };
+        let Some(step) = value.checked_div(derivative) else {
+            break;
+        };
+        let mut next = rate.checked_sub(step).ok_or(Error::Overflow)?;
+        if next <= Decimal::NEGATIVE_ONE {
+            // Stay within the domain by moving halfway towards -1 instead.
+            next = (rate + Decimal::NEGATIVE_ONE) / Decimal::TWO;
+        }
+        if (next - rate).abs() < TOLERANCE {
+            return Ok(next);
+        }
+        rate = next;
+    }
+    Err(Error::NoConvergence)
+}
+
+/// The growth factor `(1 + r)^n` and the annuity factor, i.e., the future value of payments of 1
+/// per period.
+fn factors(rate: Decimal, nper: u32, timing: PaymentTiming) -> Result<(Decimal, Decimal), Error> {
+    check_rate(rate)?;
+    if rate.is_zero() {
+        return Ok((Decimal::ONE, Decimal::from(nper)));
+    }
+    let growth = checked(|| (Decimal::ONE + rate).checked_powi(nper.into()))?;
+    let annuity = checked(|| {
+        (growth - Decimal::ONE)
+            .checked_div(rate)?
+            .checked_mul(timing_factor(rate, timing))
+    })?;
+    Ok((growth, annuity))
+}
+
+fn timing_factor(rate: Decimal, timing: PaymentTiming) -> Decimal {
+    match timing {
+        PaymentTiming::End => Decimal::ONE,
+        PaymentTiming::Beginning => Decimal::ONE + rate,
+    }
+}
+
+fn check_rate(rate: Decimal) -> Result<(), Error> {
+    if rate <= Decimal::NEGATIVE_ONE {
+        return Err(Error::InvalidArgument(format!(
+            "expected a rate greater than -1, but got {rate}"
+        )));
+    }
+    Ok(())
+}
+
+fn check_signs<'a>(mut values: impl Iterator<Item = &'a Money> + Clone) -> Result<(), Error> {
+    if !values.clone().any(|v| v.is_positive()) || !values.any(|v| v.is_negative()) {
+        return Err(Error::InvalidArgument(
+            "expected at least one positive and one negative cash flow".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn combine_all<'a>(values: impl IntoIterator<Item = &'a Money>) -> Result<Currency, Error> {
+    values.into_iter().try_fold(Currency::ZeroNone, |acc, v| {
+        currency::combine_currency(acc, v.currency())
+    })
+}
+
+fn checked(f: impl FnOnce() -> Option<Decimal>) -> Result<Decimal, Error> {
+    f().ok_or(Error::Overflow)
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::{cad, usd};
+    use anyhow::Result;
+    use expecting::*;
+    use rust_decimal_macros::dec;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn xirr_flows() -> Vec<CashFlow> {
+        vec![
+            CashFlow::new(date(2008, 1, 1), usd!(-10000)),
+            CashFlow::new(date(2008, 3, 1), usd!(2750)),
+            CashFlow::new(date(2008, 10, 30), usd!(4250)),
+            CashFlow::new(date(2009, 2, 15), usd!(3250)),
+            CashFlow::new(date(2009, 4, 1), usd!(2750)),
+        ]
+    }
+
+    #[test]
+    fn pmt__loan() -> Result<()> {
+        let payment = expect_ok!(pmt(
+            dec!(0.005),
+            360,
+            usd!(200000),
+            Money::zero(),
+            PaymentTiming::End
+        ));
+        expect_eq!(payment.currency(), Currency::USD);
+        expect_eq!(payment.amount().round_dp(12), dec!(-1199.101050305505));
+        expect_eq!(payment.round(), usd!(-1199.10));
+        Ok(())
+    }
+
+    #[test]
+    fn pmt__zero_rate() -> Result<()> {
+        let payment = pmt(dec!(0), 4, usd!(100), usd!(-20), PaymentTiming::End)?;
+        expect_eq!(payment.round(), usd!(-20));
+        expect_err!(pmt(dec!(0), 0, usd!(100), usd!(0), PaymentTiming::End));
+        Ok(())
+    }
+
+    #[test]
+    fn fv__annuity_due() -> Result<()> {
+        let value = fv(
+            dec!(0.005),
+            10,
+            usd!(-200),
+            usd!(-500),
+            PaymentTiming::Beginning,
+        )?;
+        expect_eq!(value.amount().round_dp(12), dec!(2581.403374060179));
+        Ok(())
+    }
+
+    #[test]
+    fn pv__annuity() -> Result<()> {
+        let value = pv(
+            dec!(0.08) / dec!(12),
+            240,
+            usd!(500),
+            Money::zero(),
+            PaymentTiming::End,
+        )?;
+        expect_eq!(value.round(), usd!(-59777.15));
+        Ok(())
+    }
+
+    #[test]
+    fn nper__annuity_due() -> Result<()> {
+        let periods = nper(
+            dec!(0.01),
+            usd!(-100),
+            usd!(-1000),
+            usd!(10000),
+            PaymentTiming::Beginning,
+        )?;
+        expect_eq!(periods.round_dp(8), dec!(59.67386567));
+        expect_eq!(
+            nper(dec!(0), usd!(-100), usd!(1000), usd!(0), PaymentTiming::End)?,
+            dec!(10)
+        );
+        // The payment is less than the interest.
+        expect_err!(nper(
+            dec!(0.01),
+            usd!(-100),
+            usd!(20000),
+            usd!(0),
+            PaymentTiming::End
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn npv__discounts_first_value() -> Result<()> {
+        let values = [usd!(-10000), usd!(3000), usd!(4200), usd!(6800)];
+        let value = npv(dec!(0.1), &values)?;
+        expect_eq!(value.round(), usd!(1188.44));
+        Ok(())
+    }
+
+    #[test]
+    fn irr() -> Result<()> {
+        let values = [
+            usd!(-70000),
+            usd!(12000),
+            usd!(15000),
+            usd!(18000),
+            usd!(21000),
+            usd!(26000),
+        ];
+        let rate = expect_ok!(super::irr(&values));
+        expect_eq!(rate.round_dp(10), dec!(0.0866309480));
+        expect_eq!(super::irr(&values[..3])?.round_dp(6), dec!(-0.443507));
+        Ok(())
+    }
+
+    #[test]
+    fn irr__same_signs__returns_err() -> Result<()> {
+        expect_err!(super::irr(&[usd!(100), usd!(200)]));
+        expect_err!(super::irr(&[]));
+        Ok(())
+    }
+
+    #[test]
+    fn irr__no_solution__returns_err() -> Result<()> {
+        // The net present value is positive at every rate.
+        let values = [usd!(100), usd!(-300), usd!(300)];
+        expect_eq!(expect_err!(super::irr(&values)), Error::NoConvergence);
+        Ok(())
+    }
+
+    #[test]
+    fn solve_rate__overflow__returns_err() -> Result<()> {
+        let diverging = |_| Some((Decimal::MAX, Decimal::NEGATIVE_ONE));
+        expect_eq!(expect_err!(solve_rate(diverging)), Error::Overflow);
+        Ok(())
+    }
+
+    #[test]
+    fn xnpv() -> Result<()> {
+        let value = super::xnpv(dec!(0.09), &xirr_flows())?;
+        expect_eq!(value.round(), usd!(2086.65));
+        Ok(())
+    }
+
+    #[test]
+    fn xirr() -> Result<()> {
+        let rate = expect_ok!(super::xirr(&xirr_flows()));
+        expect_eq!(rate.round_dp(9), dec!(0.373362534));
+        Ok(())
+    }
+
+    #[test]
+    fn mismatched_currency__returns_err() -> Result<()> {
+        expect_eq!(
+            expect_err!(npv(dec!(0.1), &[usd!(-100), cad!(110)])),
+            Error::MismatchedCurrency
+        );
+        expect_eq!(
+            expect_err!(pmt(dec!(0.1), 1, usd!(100), cad!(0), PaymentTiming::End)),
+            Error::MismatchedCurrency
+        );
+        let flows = [
+            CashFlow::new(date(2024, 1, 1), usd!(-100)),
+            CashFlow::new(date(2025, 1, 1), cad!(110)),
+        ];
+        expect_eq!(expect_err!(super::xirr(&flows)), Error::MismatchedCurrency);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_rate__returns_err() -> Result<()> {
+        expect_err!(npv(dec!(-1), &[usd!(100)]));
+        expect_err!(fv(dec!(-1.5), 1, usd!(1), usd!(1), PaymentTiming::End));
+        Ok(())
+    }
+}
@@ -0,0 +1,242 @@
+use crate::error::Error;
+use crate::fractional_money::FractionalMoney;
+use rust_decimal::Decimal;
+use std::fmt::{Display, Formatter};
+use std::ops::Mul;
+
+/// A unit of measure, e.g., "gallon" or "request". Units are compared by name, so "gallon" and
+/// "gal" are different units.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Unit {
+    name: String,
+}
+
+impl Unit {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into() }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Display for Unit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// An amount of a unit, e.g., 12.5 gallons.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Quantity {
+    amount: Decimal,
+    unit: Unit,
+}
+
+impl Quantity {
+    pub fn new(amount: Decimal, unit: Unit) -> Self {
+        Self { amount, unit }
+    }
+
+    pub fn amount(&self) -> Decimal {
+        self.amount
+    }
+
+    pub fn unit(&self) -> &Unit {
+        &self.unit
+    }
+
+    /// Attempts to add two quantities. Returns an error if the units do not match.
+    pub fn try_add(&self, rhs: &Self) -> Result<Self, Error> {
+        if self.unit != rhs.unit {
+            return Err(Error::MismatchedUnit);
+        }
+        let amount = self.amount.checked_add(rhs.amount).ok_or(Error::Overflow)?;
+        Ok(Self::new(amount, self.unit.clone()))
+    }
+
+    /// Attempts to subtract `rhs` from this quantity. Returns an error if the units do not match.
+    pub fn try_subtract(&self, rhs: &Self) -> Result<Self, Error> {
+        if self.unit != rhs.unit {
+            return Err(Error::MismatchedUnit);
+        }
+        let amount = self.amount.checked_sub(rhs.amount).ok_or(Error::Overflow)?;
+        Ok(Self::new(amount, self.unit.clone()))
+    }
+}
+
+impl Display for Quantity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.amount, self.unit)
+    }
+}
+
+/// A price per unit, which may be more precise than the currency, e.g., 3.999 USD per gallon.
+/// Multiplying it by a `Quantity` of the same unit gives the `FractionalMoney` cost, which can
+/// then be rounded.
+///
+/// The price has at most a maximum number of decimal places, so that an inexact price (e.g., the
+/// result of a division) cannot be used by accident.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnitPrice {
+    price: FractionalMoney,
+    per: Unit,
+    max_precision: u32,
+}
+
+impl UnitPrice {
+    /// The maximum number of decimal places of a price created with `new()`, e.g., enough for
+    /// 0.000012 USD per request.
+    pub const DEFAULT_MAX_PRECISION: u32 = 6;
+
+    /// Creates a price per unit with at most `DEFAULT_MAX_PRECISION` decimal places.
+    /// Returns an `InvalidMoneyValue` error if the price has more decimal places.
+    pub fn new(price: FractionalMoney, per: Unit) -> Result<Self, Error> {
+        Self::with_max_precision(price, per, Self::DEFAULT_MAX_PRECISION)
+    }
+
+    /// Creates a price per unit with at most `max_precision` decimal places, ignoring trailing
+    /// zeros. Returns an `InvalidMoneyValue` error if the price has more decimal places.
+    pub fn with_max_precision(
+        price: FractionalMoney,
+        per: Unit,
+        max_precision: u32,
+    ) -> Result<Self, Error> {
+        if price.amount().normalize().scale() > max_precision {
+            return Err(Error::InvalidMoneyValue(format!(
+                "the unit price {price} has more than {max_precision} decimal places"
+            )));
+        }
+        Ok(Self {
+            price,
+            per,
+            max_precision,
+        })
+    }
+
+    pub fn price(&self) -> FractionalMoney {
+        self.price
+    }
+
+    pub fn per(&self) -> &Unit {
+        &self.per
+    }
+
+    pub fn max_precision(&self) -> u32 {
+        self.max_precision
+    }
+
+    /// Attempts to multiply this price by a quantity, e.g., 3.999 USD per gallon times 12.5
+    /// gallons is 49.9875 USD. Returns an error if the units do not match.
+    pub fn try_mul(&self, quantity: &Quantity) -> Result<FractionalMoney, Error> {
+        if self.per != quantity.unit {
+            return Err(Error::MismatchedUnit);
+        }
+        let amount = self
+            .price
+            .amount()
+            .checked_mul(quantity.amount)
+            .ok_or(Error::Overflow)?;
+        FractionalMoney::new(amount, self.price.currency())
+    }
+}
+
+impl Display for UnitPrice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.price, self.per)
+    }
+}
+
+/// Panics if the units do not match. Use `UnitPrice::try_mul()` to handle this as an error.
+impl Mul<&Quantity> for &UnitPrice {
+    type Output = FractionalMoney;
+
+    fn mul(self, rhs: &Quantity) -> Self::Output {
+        self.try_mul(rhs).unwrap()
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::currency::Currency;
+    use anyhow::Result;
+    use expecting::*;
+    use rust_decimal_macros::dec;
+
+    fn usd(d: &str) -> FractionalMoney {
+        FractionalMoney::new(Decimal::from_str_exact(d).unwrap(), Currency::USD).unwrap()
+    }
+
+    fn gallons(amount: Decimal) -> Quantity {
+        Quantity::new(amount, Unit::new("gallon"))
+    }
+
+    #[test]
+    fn try_mul() -> Result<()> {
+        let gas = UnitPrice::new(usd("3.999"), Unit::new("gallon"))?;
+        expect_eq!(gas.try_mul(&gallons(dec!(12.5)))?, usd("49.9875"));
+        expect_eq!(&gas * &gallons(dec!(10)), usd("39.99"));
+
+        let requests = UnitPrice::new(usd("0.000012"), Unit::new("request"))?;
+        let usage = Quantity::new(dec!(1_250_000), Unit::new("request"));
+        expect_eq!(requests.try_mul(&usage)?.round(), crate::usd!(15));
+        Ok(())
+    }
+
+    #[test]
+    fn try_mul__mismatched_unit__returns_err() -> Result<()> {
+        let gas = UnitPrice::new(usd("3.999"), Unit::new("gallon"))?;
+        let litres = Quantity::new(dec!(40), Unit::new("litre"));
+        expect_eq!(expect_err!(gas.try_mul(&litres)), Error::MismatchedUnit);
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic]
+    fn mul__mismatched_unit__panics() {
+        let gas = UnitPrice::new(usd("3.999"), Unit::new("gallon")).unwrap();
+        let _ = &gas * &Quantity::new(dec!(40), Unit::new("litre"));
+    }
+
+    #[test]
+    fn new__max_precision() -> Result<()> {
+        expect_ok!(UnitPrice::new(usd("0.0000120"), Unit::new("request")));
+        expect_err!(UnitPrice::new(usd("0.0000001"), Unit::new("request")));
+
+        let price = expect_ok!(UnitPrice::with_max_precision(
+            usd("0.00000001"),
+            Unit::new("token"),
+            8
+        ));
+        expect_eq!(price.max_precision(), 8);
+        expect_err!(UnitPrice::with_max_precision(
+            usd("3.999"),
+            Unit::new("gallon"),
+            2
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn quantity__try_add() -> Result<()> {
+        let total = gallons(dec!(1.5)).try_add(&gallons(dec!(2)))?;
+        expect_eq!(total.amount(), dec!(3.5));
+        expect_eq!(total.try_subtract(&gallons(dec!(0.5)))?, gallons(dec!(3.0)));
+        expect_eq!(
+            expect_err!(total.try_add(&Quantity::new(dec!(1), Unit::new("litre")))),
+            Error::MismatchedUnit
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn display() -> Result<()> {
+        let gas = UnitPrice::new(usd("3.999"), Unit::new("gallon"))?;
+        expect_eq!(gas.to_string(), "3.999 USD/gallon");
+        expect_eq!(gallons(dec!(12.5)).to_string(), "12.5 gallon");
+        Ok(())
+    }
+}
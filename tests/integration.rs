@@ -1,5 +1,5 @@
 use anyhow::Result;
-use cashmoney::{cad, usd, Currency, Money};
+use cashmoney::{cad, usd, Currency, Money, RationalMoney};
 use expecting::*;
 use rust_decimal_macros::dec;
 
@@ -43,6 +43,22 @@ fn chain_calculation_rounded_at_end() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn chain_calculation_exact() -> Result<()> {
+    let a = usd!(1);
+
+    // FractionalMoney truncates 1/3 to 28 digits...
+    let fractional = a / dec!(3) * dec!(3);
+    expect_ne!(fractional.amount(), dec!(1));
+
+    // ... whereas RationalMoney stays exact.
+    let exact = RationalMoney::from(a) / dec!(3) * dec!(3);
+    expect_eq!(exact.round()?, usd!(1));
+    expect_eq!(exact, RationalMoney::from(a));
+
+    Ok(())
+}
+
 #[test]
 fn money_ops() -> Result<()> {
     let mut a = usd!(1);